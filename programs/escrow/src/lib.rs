@@ -1,34 +1,80 @@
+// Anchor's generated IDL handlers still call the deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use option_math::settlement::{self, Margins, Party, MARGIN_CALL_THRESHOLD};
+use option_math::{black_scholes_price, OptionKind, PricingInput, Rounding};
 
 declare_id!("FX3EgWWVrVCzgtntijpgfCT22C7HXpq6Py9DrYmDjR3E");
 
 // Constants for margin management
-const MARGIN_CALL_THRESHOLD: u64 = 20; // 20% of initial margin
 const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+const MARGIN_CALL_GRACE_PERIOD: i64 = SECONDS_PER_DAY; // Window to cure a margin call
+
+// Constants for automatic exercise
+const DEFAULT_KEEPER_BOUNTY: u64 = 5_000_000; // 0.005 SOL reward for the keeper that auto-exercises
+const EXERCISE_GRACE_PERIOD: i64 = SECONDS_PER_DAY; // Window for the owner to exercise before anyone can settle at the expiry price
+
+// Constants for exercise styles
+const MAX_EXERCISE_DATES: usize = 12; // Bermudan exercise dates per contract
+const EXERCISE_WINDOW: i64 = SECONDS_PER_DAY; // A Bermudan date is exercisable for one day
+
+// Constants for contract tenor
+const MIN_TENOR: i64 = 60 * 60; // 1 hour, allows same-day (0DTE) contracts
+const MAX_TENOR: i64 = 365 * SECONDS_PER_DAY; // 1 year
+
+// Constants for oracle price validation
+const MAX_PRICE_AGE: i64 = 60; // Seconds before a published price is considered stale
+const MAX_CONFIDENCE_BPS: u64 = 200; // Confidence interval must be within 2% of price
+const USD_DECIMALS: i32 = 6; // Settlement math uses USD with 6 decimals
+const SOL_DECIMALS: u8 = 9; // Native collateral is denominated in lamports
+const SOL_SYMBOL: &str = "SOL"; // Symbol of the feed that prices native collateral
 
 #[program]
 pub mod escrow {
     use super::*;
 
     /// Initialize a new options contract with margin accounts
+    /// series_id: Must equal the seller's next series id (SellerState.next_series_id)
     /// option_type: 0 for Call, 1 for Put
+    /// expiry_date: Expiry timestamp, between MIN_TENOR and MAX_TENOR after initiation
     /// strike: The strike price in lamports (ratio of asset price to SOL price)
     /// is_test: true for test contracts (allows past dates), false for production
     /// allow_zero_margin: true to allow zero margin for testing
+    /// exercise_style: European, American, or Bermudan with ascending exercise dates up to expiry
+    /// contract_size: Units of the underlying per contract
+    /// quantity: Number of contracts; P&L and payouts scale by contract_size * quantity
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_option(
         ctx: Context<InitializeOption>,
+        series_id: u64,
         option_type: u8,
         underlying: String,
         initiation_date: i64,
+        expiry_date: i64,
         price: u64,
         strike: u64,
         initial_margin: u64,
         is_test: bool,
         allow_zero_margin: bool,
+        exercise_style: ExerciseStyle,
+        contract_size: u64,
+        quantity: u64,
     ) -> Result<()> {
         require!(option_type <= 1, ErrorCode::InvalidOptionType);
+        require!(
+            series_id == ctx.accounts.seller_state.next_series_id,
+            ErrorCode::InvalidSeriesId
+        );
         require!(price > 0, ErrorCode::PriceMustBeNonZero);
         require!(strike > 0, ErrorCode::StrikeMustBeNonZero);
+        require!(
+            contract_size > 0 && quantity > 0,
+            ErrorCode::InvalidContractSize
+        );
         
         // Allow zero margin only if explicitly allowed AND in test mode
         if !allow_zero_margin || !is_test {
@@ -37,6 +83,42 @@ pub mod escrow {
         
         require!(underlying.len() <= 32, ErrorCode::UnderlyingTooLong);
         
+        // Settlement can only read feeds published by the configured oracle authority,
+        // the asset feed must price the underlying's base asset (e.g. AAPL for AAPL/USDC)
+        // and the collateral feed must price SOL until a token vault replaces it
+        let oracle_authority = ctx.accounts.oracle_config.authority;
+        require!(
+            ctx.accounts.asset_price_feed.authority == oracle_authority
+                && ctx.accounts.sol_price_feed.authority == oracle_authority,
+            ErrorCode::UntrustedPriceFeed
+        );
+        require!(
+            underlying.split('/').next() == Some(ctx.accounts.asset_price_feed.symbol.as_str())
+                && ctx.accounts.sol_price_feed.symbol == SOL_SYMBOL,
+            ErrorCode::PriceFeedMismatch
+        );
+        
+        let tenor = expiry_date
+            .checked_sub(initiation_date)
+            .ok_or(ErrorCode::CalculationOverflow)?;
+        require!(
+            (MIN_TENOR..=MAX_TENOR).contains(&tenor),
+            ErrorCode::InvalidExpiryDate
+        );
+        
+        if let ExerciseStyle::Bermudan { exercise_dates } = &exercise_style {
+            require!(
+                !exercise_dates.is_empty() && exercise_dates.len() <= MAX_EXERCISE_DATES,
+                ErrorCode::InvalidExerciseDates
+            );
+            require!(
+                exercise_dates.windows(2).all(|pair| pair[0] < pair[1])
+                    && exercise_dates[0] > initiation_date
+                    && exercise_dates[exercise_dates.len() - 1] <= expiry_date,
+                ErrorCode::InvalidExerciseDates
+            );
+        }
+        
         let clock = Clock::get()?;
         
         // Real contracts cannot be initiated with past dates
@@ -47,20 +129,34 @@ pub mod escrow {
             );
         }
         
+        // Reserve the series id so the seller's contracts can be enumerated in order
+        let seller_state = &mut ctx.accounts.seller_state;
+        seller_state.seller = ctx.accounts.seller.key();
+        seller_state.next_series_id = series_id
+            .checked_add(1)
+            .ok_or(ErrorCode::CalculationOverflow)?;
+        seller_state.bump = ctx.bumps.seller_state;
+        
         let option = &mut ctx.accounts.option;
         
+        option.series_id = series_id;
         option.option_type = option_type;
         option.underlying = underlying;
         option.seller = ctx.accounts.seller.key();
+        option.asset_price_feed = ctx.accounts.asset_price_feed.key();
+        option.sol_price_feed = ctx.accounts.sol_price_feed.key();
         option.initiation_date = initiation_date;
-        option.expiry_date = initiation_date + (30 * SECONDS_PER_DAY); // 30 days in seconds
+        option.expiry_date = expiry_date;
         option.status = OptionStatus::Listed;
         option.price = price;
         option.strike = strike;
+        option.contract_size = contract_size;
+        option.quantity = quantity;
         option.owner = Pubkey::default(); // No owner initially
         option.bump = ctx.bumps.option;
         option.is_test = is_test;
         option.allow_zero_margin = allow_zero_margin;
+        option.exercise_style = exercise_style;
         
         // Margin account initialization
         option.initial_margin = initial_margin;
@@ -68,12 +164,21 @@ pub mod escrow {
         option.buyer_margin = 0; // Set when purchased
         option.last_settlement_date = 0;
         option.last_settlement_price = 0;
+        option.settlement_shortfall = 0;
+        option.margin_call_date = 0;
+        option.expiry_price = 0;
+        option.keeper_bounty = DEFAULT_KEEPER_BOUNTY;
+        option.accumulated_variation = 0;
+        option.collateral_mint = Pubkey::default(); // Native SOL until a token vault is attached
+        option.collateral_decimals = SOL_DECIMALS;
         
         Ok(())
     }
 
     /// Purchase an option contract with margin deposit
     pub fn purchase_option(ctx: Context<PurchaseOption>) -> Result<()> {
+        require_native_collateral(&ctx.accounts.option)?;
+        
         let clock = Clock::get()?;
         
         // Read values we need before mutable operations
@@ -153,16 +258,22 @@ pub mod escrow {
     }
 
     /// Daily settlement - calculates P&L and adjusts margins
-    /// asset_price_usd: Current price of underlying asset in USD (with 6 decimals)
-    /// sol_price_usd: Current price of SOL in USD (with 6 decimals)
-    pub fn daily_settlement(
-        ctx: Context<DailySettlement>,
-        asset_price_usd: u64,
-        sol_price_usd: u64,
-    ) -> Result<()> {
+    /// Variation margin follows the change in the option's intrinsic value, so a
+    /// long option never pays more than it has received and the seller carries the convex risk.
+    /// Asset and SOL prices are read from the price feeds bound to the option
+    pub fn daily_settlement(ctx: Context<DailySettlement>) -> Result<()> {
         let option = &mut ctx.accounts.option;
         let clock = Clock::get()?;
         
+        require!(
+            ctx.accounts.asset_price_feed.key() == option.asset_price_feed
+                && ctx.accounts.sol_price_feed.key() == option.sol_price_feed,
+            ErrorCode::PriceFeedMismatch
+        );
+        
+        let asset_price_usd = read_price_usd(&ctx.accounts.asset_price_feed, clock.unix_timestamp)?;
+        let sol_price_usd = read_price_usd(&ctx.accounts.sol_price_feed, clock.unix_timestamp)?;
+        
         require!(
             option.status == OptionStatus::Owned,
             ErrorCode::OptionNotOwned
@@ -183,80 +294,50 @@ pub mod escrow {
         }
         
         // Calculate current asset value in SOL terms
-        // asset_value_in_sol = (asset_price_usd * 10^9) / sol_price_usd
-        require!(sol_price_usd > 0, ErrorCode::InvalidPrice);
-        let current_ratio = (asset_price_usd as u128)
-            .checked_mul(1_000_000_000) // Scale to lamports
-            .ok_or(ErrorCode::CalculationOverflow)?
-            .checked_div(sol_price_usd as u128)
-            .ok_or(ErrorCode::CalculationOverflow)? as u64;
+        let current_ratio = calculate_ratio(asset_price_usd, sol_price_usd, option.collateral_decimals)?;
         
-        // Calculate P&L based on movement from strike price
+        // Calculate P&L based on the change in intrinsic value since the last settlement
         let strike = option.strike;
         
-        // For first settlement, use strike as reference
+        // For first settlement, use strike as reference (zero intrinsic value)
         let reference_price = if option.last_settlement_price == 0 {
             strike
         } else {
             option.last_settlement_price
         };
         
-        let price_diff = if current_ratio > reference_price {
-            current_ratio - reference_price
-        } else {
-            reference_price - current_ratio
-        };
-        
         // Determine who gains/loses based on option type and price movement
         let (buyer_gain, seller_gain) = calculate_pnl(
             option.option_type,
             current_ratio,
             reference_price,
-            price_diff,
-        );
+            strike,
+            option.contract_size,
+            option.quantity,
+        )?;
         
         // Calculate margin call threshold (20% of initial margin)
-        let margin_threshold = option.initial_margin
-            .checked_mul(MARGIN_CALL_THRESHOLD)
-            .ok_or(ErrorCode::CalculationOverflow)?
-            .checked_div(100)
-            .ok_or(ErrorCode::CalculationOverflow)?;
+        let margin_threshold = settlement::margin_threshold(option.initial_margin, MARGIN_CALL_THRESHOLD)
+            .map_err(|_| ErrorCode::CalculationOverflow)?;
+        
+        // Adjust margins with margin call protection, capping any transfer at the threshold
+        let mut margins = Margins {
+            buyer: option.buyer_margin,
+            seller: option.seller_margin,
+            accumulated_variation: option.accumulated_variation,
+        };
+        let margin_called = settlement::apply_variation(&mut margins, buyer_gain, seller_gain, margin_threshold)
+            .map_err(|_| ErrorCode::CalculationOverflow)?;
+        option.buyer_margin = margins.buyer;
+        option.seller_margin = margins.seller;
+        option.accumulated_variation = margins.accumulated_variation;
         
-        // Adjust margins with margin call protection
-        if buyer_gain > 0 {
-            // Check if seller would fall below threshold
-            if buyer_gain >= option.seller_margin || option.seller_margin.saturating_sub(buyer_gain) <= margin_threshold {
-                // Cap the transfer to avoid negative or threshold breach
-                let max_transfer = option.seller_margin.saturating_sub(margin_threshold);
-                option.buyer_margin = option.buyer_margin.checked_add(max_transfer)
-                    .ok_or(ErrorCode::CalculationOverflow)?;
-                option.seller_margin = margin_threshold;
-                option.status = OptionStatus::MarginCalled;
-                msg!("Margin call triggered - seller margin exhausted at {}%, positions forcibly settled", 
-                     (margin_threshold * 100) / option.initial_margin);
-            } else {
-                option.buyer_margin = option.buyer_margin.checked_add(buyer_gain)
-                    .ok_or(ErrorCode::CalculationOverflow)?;
-                option.seller_margin = option.seller_margin.checked_sub(buyer_gain)
-                    .ok_or(ErrorCode::InsufficientMargin)?;
-            }
-        } else if seller_gain > 0 {
-            // Check if buyer would fall below threshold
-            if seller_gain >= option.buyer_margin || option.buyer_margin.saturating_sub(seller_gain) <= margin_threshold {
-                // Cap the transfer to avoid negative or threshold breach
-                let max_transfer = option.buyer_margin.saturating_sub(margin_threshold);
-                option.seller_margin = option.seller_margin.checked_add(max_transfer)
-                    .ok_or(ErrorCode::CalculationOverflow)?;
-                option.buyer_margin = margin_threshold;
-                option.status = OptionStatus::MarginCalled;
-                msg!("Margin call triggered - buyer margin exhausted at {}%, positions forcibly settled",
-                     (margin_threshold * 100) / option.initial_margin);
-            } else {
-                option.seller_margin = option.seller_margin.checked_add(seller_gain)
-                    .ok_or(ErrorCode::CalculationOverflow)?;
-                option.buyer_margin = option.buyer_margin.checked_sub(seller_gain)
-                    .ok_or(ErrorCode::InsufficientMargin)?;
-            }
+        if let Some(party) = margin_called {
+            option.status = OptionStatus::MarginCalled;
+            option.margin_call_date = clock.unix_timestamp;
+            msg!("Margin call triggered - {} margin exhausted at {}%, positions forcibly settled",
+                 if party == Party::Seller { "seller" } else { "buyer" },
+                 (margin_threshold * 100) / option.initial_margin);
         }
         
         // Update settlement tracking
@@ -266,76 +347,383 @@ pub mod escrow {
         Ok(())
     }
 
-    /// Exercise an option contract (only on expiration date)
-    /// Final settlement with reference price check
-    pub fn exercise_option(
-        ctx: Context<ExerciseOption>,
-        asset_price_usd: u64,
-        sol_price_usd: u64,
-    ) -> Result<()> {
-        let option = &mut ctx.accounts.option;
+    /// Exercise an option contract according to its exercise style
+    /// European options on or after expiry, American options at any time,
+    /// Bermudan options on one of their exercise dates or after expiry.
+    /// Pays the intrinsic value, net of variation margin already transferred
+    /// by daily settlement, and returns each party's remaining margin
+    pub fn exercise_option(ctx: Context<ExerciseOption>) -> Result<()> {
+        require_native_collateral(&ctx.accounts.option)?;
+        
         let clock = Clock::get()?;
         
+        // Read values before moving lamports
+        let status = ctx.accounts.option.status.clone();
+        let owner = ctx.accounts.option.owner;
+        let seller = ctx.accounts.option.seller;
+        let option_type = ctx.accounts.option.option_type;
+        let strike = ctx.accounts.option.strike;
+        let buyer_margin = ctx.accounts.option.buyer_margin;
+        let seller_margin = ctx.accounts.option.seller_margin;
+        let expiry_price = ctx.accounts.option.expiry_price;
+        let accumulated_variation = ctx.accounts.option.accumulated_variation;
+        
         require!(
-            option.status == OptionStatus::Owned,
+            status == OptionStatus::Owned,
             ErrorCode::OptionNotOwned
         );
         
         require!(
-            ctx.accounts.owner.key() == option.owner,
+            ctx.accounts.owner.key() == owner,
             ErrorCode::Unauthorized
         );
         
-        // European option: Can only exercise ON or AFTER expiry date
-        // Skip this check for test mode contracts
-        if !option.is_test {
-            require!(
-                clock.unix_timestamp >= option.expiry_date,
-                ErrorCode::CannotExerciseBeforeExpiry
-            );
-        }
+        require!(
+            ctx.accounts.seller.key() == seller,
+            ErrorCode::Unauthorized
+        );
         
-        // Calculate final settlement value
-        require!(sol_price_usd > 0, ErrorCode::InvalidPrice);
-        let final_ratio = (asset_price_usd as u128)
-            .checked_mul(1_000_000_000)
-            .ok_or(ErrorCode::CalculationOverflow)?
-            .checked_div(sol_price_usd as u128)
-            .ok_or(ErrorCode::CalculationOverflow)? as u64;
+        require!(
+            ctx.accounts.asset_price_feed.key() == ctx.accounts.option.asset_price_feed
+                && ctx.accounts.sol_price_feed.key() == ctx.accounts.option.sol_price_feed,
+            ErrorCode::PriceFeedMismatch
+        );
+        
+        check_exercise_window(&ctx.accounts.option, clock.unix_timestamp)?;
+        
+        // Calculate final settlement value, using the price recorded at expiry if there is one
+        let final_ratio = if expiry_price > 0 {
+            expiry_price
+        } else {
+            let asset_price_usd = read_price_usd(&ctx.accounts.asset_price_feed, clock.unix_timestamp)?;
+            let sol_price_usd = read_price_usd(&ctx.accounts.sol_price_feed, clock.unix_timestamp)?;
+            calculate_ratio(asset_price_usd, sol_price_usd, ctx.accounts.option.collateral_decimals)?
+        };
         
         // Calculate final P&L
-        let strike = option.strike;
         let settlement_value = calculate_settlement_value(
-            option.option_type,
+            option_type,
             final_ratio,
             strike,
-        );
+            ctx.accounts.option.contract_size,
+            ctx.accounts.option.quantity,
+        )?;
+        
+        let (owner_amount, seller_amount, shortfall) = calculate_exercise_split(
+            settlement_value,
+            accumulated_variation,
+            buyer_margin,
+            seller_margin,
+        )?;
         
-        msg!("Exercise settlement - Asset/SOL ratio: {}, Strike: {}, Settlement: {}", 
-            final_ratio, strike, settlement_value);
+        msg!("Exercise settlement - Asset/SOL ratio: {}, Strike: {}, Settlement: {}, Variation already paid: {}", 
+            final_ratio, strike, settlement_value, accumulated_variation);
+        
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            owner_amount,
+        )?;
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            seller_amount,
+        )?;
+        
+        if shortfall > 0 {
+            msg!("Seller margin exhausted - shortfall {}", shortfall);
+        }
         
         // Mark as exercised/expired
+        let option = &mut ctx.accounts.option;
         option.status = OptionStatus::Expired;
         option.last_settlement_price = final_ratio;
+        option.buyer_margin = 0;
+        option.seller_margin = 0;
+        option.settlement_shortfall = shortfall;
         
         Ok(())
     }
 
-    /// Mark expired options
-    pub fn expire_option(ctx: Context<ExpireOption>) -> Result<()> {
+    /// Automatically exercise an in-the-money option after expiry (permissionless crank)
+    /// Pays intrinsic value to the owner less the keeper bounty, which goes to the keeper.
+    /// Only options whose intrinsic value exceeds the bounty are auto-exercised; the rest
+    /// are settled without a bounty by expire_option once the exercise grace period ends
+    pub fn auto_exercise(ctx: Context<AutoExercise>) -> Result<()> {
+        require_native_collateral(&ctx.accounts.option)?;
+        
+        let clock = Clock::get()?;
+        
+        // Read values before moving lamports
+        let status = ctx.accounts.option.status.clone();
+        let expiry_date = ctx.accounts.option.expiry_date;
+        let expiry_price = ctx.accounts.option.expiry_price;
+        let option_type = ctx.accounts.option.option_type;
+        let strike = ctx.accounts.option.strike;
+        let buyer_margin = ctx.accounts.option.buyer_margin;
+        let seller_margin = ctx.accounts.option.seller_margin;
+        let keeper_bounty = ctx.accounts.option.keeper_bounty;
+        let accumulated_variation = ctx.accounts.option.accumulated_variation;
+        
+        require!(
+            status == OptionStatus::Owned,
+            ErrorCode::OptionNotOwned
+        );
+        
+        require!(
+            clock.unix_timestamp >= expiry_date,
+            ErrorCode::OptionNotExpired
+        );
+        
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.option.owner
+                && ctx.accounts.seller.key() == ctx.accounts.option.seller,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            ctx.accounts.asset_price_feed.key() == ctx.accounts.option.asset_price_feed
+                && ctx.accounts.sol_price_feed.key() == ctx.accounts.option.sol_price_feed,
+            ErrorCode::PriceFeedMismatch
+        );
+        
+        // Settle at the price recorded at expiry if there is one, otherwise at the oracle price
+        let final_ratio = if expiry_price > 0 {
+            expiry_price
+        } else {
+            let asset_price_usd = read_price_usd(&ctx.accounts.asset_price_feed, clock.unix_timestamp)?;
+            let sol_price_usd = read_price_usd(&ctx.accounts.sol_price_feed, clock.unix_timestamp)?;
+            calculate_ratio(asset_price_usd, sol_price_usd, ctx.accounts.option.collateral_decimals)?
+        };
+        
+        let settlement_value = calculate_settlement_value(
+            option_type,
+            final_ratio,
+            strike,
+            ctx.accounts.option.contract_size,
+            ctx.accounts.option.quantity,
+        )?;
+        
+        require!(
+            settlement_value > keeper_bounty,
+            ErrorCode::BelowAutoExerciseThreshold
+        );
+        
+        let (owner_amount, seller_amount, shortfall) = calculate_exercise_split(
+            settlement_value,
+            accumulated_variation,
+            buyer_margin,
+            seller_margin,
+        )?;
+        let keeper_reward = keeper_bounty.min(owner_amount);
+        let owner_amount = owner_amount - keeper_reward;
+        
+        msg!("Auto-exercise settlement - Asset/SOL ratio: {}, Strike: {}, Settlement: {}, Keeper reward: {}",
+            final_ratio, strike, settlement_value, keeper_reward);
+        
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            owner_amount,
+        )?;
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            seller_amount,
+        )?;
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.keeper.to_account_info(),
+            keeper_reward,
+        )?;
+        
+        if shortfall > 0 {
+            msg!("Seller margin exhausted - shortfall {}", shortfall);
+        }
+        
+        let option = &mut ctx.accounts.option;
+        option.status = OptionStatus::Expired;
+        option.last_settlement_price = final_ratio;
+        option.buyer_margin = 0;
+        option.seller_margin = 0;
+        option.settlement_shortfall = shortfall;
+        
+        Ok(())
+    }
+
+    /// Set the reward paid to a keeper that auto-exercises the option (owner only)
+    pub fn set_keeper_bounty(ctx: Context<SetKeeperBounty>, keeper_bounty: u64) -> Result<()> {
         let option = &mut ctx.accounts.option;
+        
+        require!(
+            option.status == OptionStatus::Owned,
+            ErrorCode::OptionNotOwned
+        );
+        
+        require!(
+            ctx.accounts.owner.key() == option.owner,
+            ErrorCode::Unauthorized
+        );
+        
+        option.keeper_bounty = keeper_bounty;
+        
+        Ok(())
+    }
+
+    /// Expire an option past its expiry date (permissionless crank)
+    /// Listed options are simply marked Expired. Owned options that finish
+    /// out of the money return both margins; in-the-money options keep their
+    /// margins and record the expiry price for exercise. Once EXERCISE_GRACE_PERIOD
+    /// has passed after expiry, anyone can settle an in-the-money option at the
+    /// recorded price, paying the owner exactly what exercise would have
+    pub fn expire_option(ctx: Context<ExpireOption>) -> Result<()> {
         let clock = Clock::get()?;
         
+        // Read values before moving lamports
+        let status = ctx.accounts.option.status.clone();
+        let expiry_date = ctx.accounts.option.expiry_date;
+        let expiry_price = ctx.accounts.option.expiry_price;
+        
+        require!(
+            status == OptionStatus::Owned || status == OptionStatus::Listed,
+            ErrorCode::CannotExpireOption
+        );
+        
         require!(
-            clock.unix_timestamp >= option.expiry_date,
+            clock.unix_timestamp >= expiry_date,
             ErrorCode::OptionNotExpired
         );
         
+        if status == OptionStatus::Listed {
+            ctx.accounts.option.status = OptionStatus::Expired;
+            return Ok(());
+        }
+        
+        require_native_collateral(&ctx.accounts.option)?;
+        
+        require!(
+            ctx.accounts.seller.key() == ctx.accounts.option.seller,
+            ErrorCode::Unauthorized
+        );
+        
+        // An in-the-money option that has already had its expiry price recorded is
+        // left for its owner to exercise until the grace period ends
+        let final_ratio = if expiry_price > 0 {
+            require!(
+                clock.unix_timestamp >= expiry_date.saturating_add(EXERCISE_GRACE_PERIOD),
+                ErrorCode::AwaitingExercise
+            );
+            expiry_price
+        } else {
+            require!(
+                ctx.accounts.asset_price_feed.key() == ctx.accounts.option.asset_price_feed
+                    && ctx.accounts.sol_price_feed.key() == ctx.accounts.option.sol_price_feed,
+                ErrorCode::PriceFeedMismatch
+            );
+            
+            let asset_price_usd = read_price_usd(&ctx.accounts.asset_price_feed, clock.unix_timestamp)?;
+            let sol_price_usd = read_price_usd(&ctx.accounts.sol_price_feed, clock.unix_timestamp)?;
+            calculate_ratio(asset_price_usd, sol_price_usd, ctx.accounts.option.collateral_decimals)?
+        };
+        
+        let settlement_value = calculate_settlement_value(
+            ctx.accounts.option.option_type,
+            final_ratio,
+            ctx.accounts.option.strike,
+            ctx.accounts.option.contract_size,
+            ctx.accounts.option.quantity,
+        )?;
+        
+        if expiry_price == 0 && settlement_value > 0 {
+            // Leave in-the-money options for exercise at the recorded price
+            let option = &mut ctx.accounts.option;
+            option.expiry_price = final_ratio;
+            msg!("Option expired in the money - Asset/SOL ratio: {}, Strike: {}, awaiting exercise",
+                 final_ratio, option.strike);
+            return Ok(());
+        }
+        
+        // Out of the money, or in the money and unexercised past the grace period:
+        // pay the intrinsic value net of accumulated variation and return both margins
+        let owner = ctx.accounts.owner.as_ref().ok_or(ErrorCode::Unauthorized)?;
+        require!(
+            owner.key() == ctx.accounts.option.owner,
+            ErrorCode::Unauthorized
+        );
+        
+        let (owner_amount, seller_amount, shortfall) = calculate_exercise_split(
+            settlement_value,
+            ctx.accounts.option.accumulated_variation,
+            ctx.accounts.option.buyer_margin,
+            ctx.accounts.option.seller_margin,
+        )?;
+        
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &owner.to_account_info(),
+            owner_amount,
+        )?;
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            seller_amount,
+        )?;
+        
+        if settlement_value > 0 {
+            msg!("Unexercised option settled at expiry - Asset/SOL ratio: {}, Settlement: {}", final_ratio, settlement_value);
+        } else {
+            msg!("Option expired out of the money - Asset/SOL ratio: {}, margins returned", final_ratio);
+        }
+        
+        if shortfall > 0 {
+            msg!("Seller margin exhausted - shortfall {}", shortfall);
+        }
+        
+        let option = &mut ctx.accounts.option;
         option.status = OptionStatus::Expired;
+        option.last_settlement_price = final_ratio;
+        option.buyer_margin = 0;
+        option.seller_margin = 0;
+        option.settlement_shortfall = shortfall;
         
         Ok(())
     }
 
+    /// Quote the Black-Scholes premium for an option at current oracle prices
+    /// volatility: Annualized volatility (1e9 = 100%)
+    /// rate: Annualized risk-free rate (1e9 = 100%)
+    /// Returns the fair premium for the whole contract in collateral base units
+    pub fn quote_premium(ctx: Context<QuotePremium>, volatility: u64, rate: i64) -> Result<u64> {
+        let clock = Clock::get()?;
+        let option = &ctx.accounts.option;
+        
+        require!(
+            ctx.accounts.asset_price_feed.key() == option.asset_price_feed
+                && ctx.accounts.sol_price_feed.key() == option.sol_price_feed,
+            ErrorCode::PriceFeedMismatch
+        );
+        
+        let asset_price_usd = read_price_usd(&ctx.accounts.asset_price_feed, clock.unix_timestamp)?;
+        let sol_price_usd = read_price_usd(&ctx.accounts.sol_price_feed, clock.unix_timestamp)?;
+        let current_ratio = calculate_ratio(asset_price_usd, sol_price_usd, option.collateral_decimals)?;
+        
+        let kind = OptionKind::from_u8(option.option_type).ok_or(ErrorCode::InvalidOptionType)?;
+        let input = PricingInput {
+            spot: current_ratio,
+            strike: option.strike,
+            time_to_expiry: option.expiry_date.saturating_sub(clock.unix_timestamp),
+            volatility,
+            rate,
+        };
+        let unit_premium = black_scholes_price(kind, &input).map_err(|_| ErrorCode::PricingFailed)?;
+        let premium = scale_by_notional(unit_premium, option.contract_size, option.quantity)?;
+        
+        msg!("Quoted premium - Asset/SOL ratio: {}, Strike: {}, Premium: {}",
+             current_ratio, option.strike, premium);
+        
+        Ok(premium)
+    }
+
     /// Delist an option (seller can cancel if not owned)
     pub fn delist_option(ctx: Context<DelistOption>) -> Result<()> {
         let option = &mut ctx.accounts.option;
@@ -355,10 +743,78 @@ pub mod escrow {
         Ok(())
     }
 
+    /// Close a finished option and reclaim its rent
+    /// Only valid once the contract is Expired, Delisted or MarginCalled and
+    /// both margins have been paid out; all remaining lamports go to the seller.
+    /// A token option's vault is closed too if settlement has not already closed it
+    pub fn close_option(ctx: Context<CloseOption>) -> Result<()> {
+        let option = &ctx.accounts.option;
+        
+        require!(
+            ctx.accounts.seller.key() == option.seller,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            matches!(
+                option.status,
+                OptionStatus::Expired | OptionStatus::Delisted | OptionStatus::MarginCalled
+            ),
+            ErrorCode::OptionNotTerminal
+        );
+        
+        require!(
+            option.buyer_margin == 0 && option.seller_margin == 0,
+            ErrorCode::MarginsNotDistributed
+        );
+        
+        if option.collateral_mint != Pubkey::default() && !ctx.accounts.vault.data_is_empty() {
+            let token_program = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(ErrorCode::TokenCollateralOption)?;
+            let collateral_mint = ctx
+                .accounts
+                .collateral_mint
+                .as_ref()
+                .ok_or(ErrorCode::TokenCollateralOption)?;
+            let seller_token_account = ctx
+                .accounts
+                .seller_token_account
+                .as_ref()
+                .ok_or(ErrorCode::TokenCollateralOption)?;
+            require!(
+                collateral_mint.key() == option.collateral_mint,
+                ErrorCode::CollateralMintMismatch
+            );
+            require!(
+                seller_token_account.owner == option.seller,
+                ErrorCode::Unauthorized
+            );
+            let series_id = option.series_id.to_le_bytes();
+            let signer_seeds: &[&[&[u8]]] = &[&[b"option", option.seller.as_ref(), &series_id, &[option.bump]]];
+            
+            close_vault(
+                &ctx.accounts.vault.to_account_info(),
+                &seller_token_account.to_account_info(),
+                collateral_mint,
+                &ctx.accounts.seller.to_account_info(),
+                &option.to_account_info(),
+                &token_program.to_account_info(),
+                signer_seeds,
+            )?;
+        }
+        
+        Ok(())
+    }
+
     /// Resell an option to a new buyer
     /// Current owner sells to new buyer at a new price
     /// Returns margin to previous owner and collects margin from new buyer
     pub fn resell_option(ctx: Context<ResellOption>, resell_price: u64) -> Result<()> {
+        require_native_collateral(&ctx.accounts.option)?;
+        
         let clock = Clock::get()?;
         
         // Read values before mutable operations
@@ -405,8 +861,11 @@ pub mod escrow {
         )?;
         
         // Step 2: Return old buyer's margin from option account to previous owner
-        **ctx.accounts.option.to_account_info().try_borrow_mut_lamports()? -= old_buyer_margin;
-        **ctx.accounts.current_owner.to_account_info().try_borrow_mut_lamports()? += old_buyer_margin;
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.current_owner.to_account_info(),
+            old_buyer_margin,
+        )?;
         
         // Step 3: Collect new buyer's margin and transfer to option account
         let new_margin_ix = anchor_lang::solana_program::system_instruction::transfer(
@@ -433,73 +892,1288 @@ pub mod escrow {
         
         Ok(())
     }
-}
 
-// Helper function to calculate P&L for daily settlement
-fn calculate_pnl(
-    option_type: u8,
-    current_price: u64,
-    reference_price: u64,
-    price_diff: u64,
-) -> (u64, u64) {
-    // Returns (buyer_gain, seller_gain)
-    
-    if option_type == 0 {
-        // Call option: Buyer gains when price increases
-        if current_price > reference_price {
-            (price_diff, 0)
-        } else {
-            (0, price_diff)
-        }
-    } else {
-        // Put option: Buyer gains when price decreases
-        if current_price < reference_price {
-            (price_diff, 0)
-        } else {
-            (0, price_diff)
-        }
+    /// Create the oracle config naming the publisher whose feeds options may settle against
+    /// Only the program's upgrade authority can create it, and becomes its admin
+    pub fn initialize_oracle_config(ctx: Context<InitializeOracleConfig>, authority: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.oracle_config;
+        
+        config.admin = ctx.accounts.admin.key();
+        config.authority = authority;
+        config.bump = ctx.bumps.oracle_config;
+        
+        Ok(())
     }
-}
 
-// Helper function to calculate final settlement value
-fn calculate_settlement_value(
-    option_type: u8,
-    final_price: u64,
-    strike_price: u64,
-) -> i64 {
-    if option_type == 0 {
-        // Call option: max(final_price - strike, 0)
-        if final_price > strike_price {
-            (final_price - strike_price) as i64
-        } else {
-            0
-        }
-    } else {
-        // Put option: max(strike - final_price, 0)
-        if strike_price > final_price {
-            (strike_price - final_price) as i64
-        } else {
-            0
-        }
+    /// Change the configured oracle authority (admin only)
+    /// Options already created keep the feeds they were validated against
+    pub fn set_oracle_authority(ctx: Context<SetOracleAuthority>, authority: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.oracle_config;
+        
+        require!(
+            ctx.accounts.admin.key() == config.admin,
+            ErrorCode::Unauthorized
+        );
+        
+        config.authority = authority;
+        
+        Ok(())
     }
+
+    /// Create a price feed owned by a publisher authority
+    /// symbol: Asset the feed prices in USD (e.g. "AAPL", "SOL")
+    /// exponent: Power of ten applied to published prices (e.g. -6 for 6 decimals)
+    pub fn initialize_price_feed(
+        ctx: Context<InitializePriceFeed>,
+        symbol: String,
+        exponent: i32,
+    ) -> Result<()> {
+        require!(symbol.len() <= 16, ErrorCode::SymbolTooLong);
+        require!(
+            (-18..=18).contains(&exponent),
+            ErrorCode::InvalidPriceExponent
+        );
+        
+        let feed = &mut ctx.accounts.price_feed;
+        
+        feed.authority = ctx.accounts.authority.key();
+        feed.symbol = symbol;
+        feed.price = 0; // No price until first publish
+        feed.confidence = 0;
+        feed.exponent = exponent;
+        feed.publish_time = 0;
+        feed.bump = ctx.bumps.price_feed;
+        
+        Ok(())
+    }
+
+    /// Publish a new price to a feed (publisher authority only)
+    /// price: Price in USD scaled by 10^exponent
+    /// confidence: Confidence interval in the same units as price
+    /// publish_time: Unix timestamp the price was observed at
+    pub fn update_price_feed(
+        ctx: Context<UpdatePriceFeed>,
+        price: i64,
+        confidence: u64,
+        publish_time: i64,
+    ) -> Result<()> {
+        let feed = &mut ctx.accounts.price_feed;
+        let clock = Clock::get()?;
+        
+        require!(
+            ctx.accounts.authority.key() == feed.authority,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(price > 0, ErrorCode::InvalidPrice);
+        
+        // Prices cannot come from the future or go back in time
+        require!(
+            publish_time <= clock.unix_timestamp && publish_time >= feed.publish_time,
+            ErrorCode::InvalidPublishTime
+        );
+        
+        feed.price = price;
+        feed.confidence = confidence;
+        feed.publish_time = publish_time;
+        
+        Ok(())
+    }
+
+    /// Top up margin on a margin-called contract
+    /// The under-margined party deposits lamports within the grace window;
+    /// restoring their margin to the initial margin returns the contract to Owned
+    pub fn top_up_margin(ctx: Context<TopUpMargin>, amount: u64) -> Result<()> {
+        require_native_collateral(&ctx.accounts.option)?;
+        
+        let clock = Clock::get()?;
+        
+        // Read values before mutable operations
+        let status = ctx.accounts.option.status.clone();
+        let owner = ctx.accounts.option.owner;
+        let seller = ctx.accounts.option.seller;
+        let is_test = ctx.accounts.option.is_test;
+        let margin_call_date = ctx.accounts.option.margin_call_date;
+        let initial_margin = ctx.accounts.option.initial_margin;
+        let buyer_margin = ctx.accounts.option.buyer_margin;
+        let seller_margin = ctx.accounts.option.seller_margin;
+        
+        require!(
+            status == OptionStatus::MarginCalled,
+            ErrorCode::OptionNotMarginCalled
+        );
+        
+        require!(amount > 0, ErrorCode::MarginMustBeNonZero);
+        
+        // Only enforce the grace window for production contracts
+        if !is_test {
+            require!(
+                clock.unix_timestamp < margin_call_date + MARGIN_CALL_GRACE_PERIOD,
+                ErrorCode::GracePeriodElapsed
+            );
+        }
+        
+        // Only the party whose margin fell to the threshold can cure the call
+        let depositor = ctx.accounts.depositor.key();
+        let is_buyer = if depositor == owner && buyer_margin < seller_margin {
+            true
+        } else if depositor == seller && seller_margin < buyer_margin {
+            false
+        } else {
+            return err!(ErrorCode::Unauthorized);
+        };
+        
+        let top_up_ix = anchor_lang::solana_program::system_instruction::transfer(
+            &depositor,
+            &ctx.accounts.option.to_account_info().key(),
+            amount,
+        );
+        
+        anchor_lang::solana_program::program::invoke(
+            &top_up_ix,
+            &[
+                ctx.accounts.depositor.to_account_info(),
+                ctx.accounts.option.to_account_info(),
+            ],
+        )?;
+        
+        let option = &mut ctx.accounts.option;
+        let new_margin = if is_buyer {
+            option.buyer_margin = buyer_margin
+                .checked_add(amount)
+                .ok_or(ErrorCode::CalculationOverflow)?;
+            option.buyer_margin
+        } else {
+            option.seller_margin = seller_margin
+                .checked_add(amount)
+                .ok_or(ErrorCode::CalculationOverflow)?;
+            option.seller_margin
+        };
+        
+        if new_margin >= initial_margin {
+            option.status = OptionStatus::Owned;
+            option.margin_call_date = 0;
+            msg!("Margin call cured - margin restored to {}", new_margin);
+        } else {
+            msg!("Margin topped up to {}, {} required to cure", new_margin, initial_margin);
+        }
+        
+        Ok(())
+    }
+
+    /// Close out a margin-called contract
+    /// Returns each party's margin, records the final settlement price and
+    /// closes the option account once the grace window has passed
+    pub fn close_margin_called(ctx: Context<CloseMarginCalled>) -> Result<()> {
+        require_native_collateral(&ctx.accounts.option)?;
+        
+        let clock = Clock::get()?;
+        
+        // Read values before moving lamports
+        let status = ctx.accounts.option.status.clone();
+        let owner = ctx.accounts.option.owner;
+        let seller = ctx.accounts.option.seller;
+        let is_test = ctx.accounts.option.is_test;
+        let margin_call_date = ctx.accounts.option.margin_call_date;
+        let buyer_margin = ctx.accounts.option.buyer_margin;
+        let seller_margin = ctx.accounts.option.seller_margin;
+        let final_price = ctx.accounts.option.last_settlement_price;
+        
+        require!(
+            status == OptionStatus::MarginCalled,
+            ErrorCode::OptionNotMarginCalled
+        );
+        
+        require!(
+            ctx.accounts.owner.key() == owner,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            ctx.accounts.seller.key() == seller,
+            ErrorCode::Unauthorized
+        );
+        
+        // Give the under-margined party the full grace window to top up
+        if !is_test {
+            require!(
+                clock.unix_timestamp >= margin_call_date + MARGIN_CALL_GRACE_PERIOD,
+                ErrorCode::GracePeriodActive
+            );
+        }
+        
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            buyer_margin,
+        )?;
+        release_lamports(
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            seller_margin,
+        )?;
+        
+        msg!("Margin call closed out - final settlement price: {}, buyer margin: {}, seller margin: {}",
+             final_price, buyer_margin, seller_margin);
+        
+        // Remaining rent is returned to the seller when the account is closed
+        let option = &mut ctx.accounts.option;
+        option.status = OptionStatus::Expired;
+        option.buyer_margin = 0;
+        option.seller_margin = 0;
+        
+        Ok(())
+    }
+
+    /// Attach a collateral mint to a listed option and create its token vault
+    /// From then on premium, strike and margins are denominated in the mint's
+    /// base units, and the collateral price feed replaces the option's SOL price feed.
+    /// The vault is a token account at PDA ["vault", option] owned by the option
+    pub fn initialize_token_vault(ctx: Context<InitializeTokenVault>) -> Result<()> {
+        let option = &mut ctx.accounts.option;
+        
+        require!(
+            option.status == OptionStatus::Listed,
+            ErrorCode::OptionNotAvailable
+        );
+        
+        require!(
+            ctx.accounts.seller.key() == option.seller,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            option.collateral_mint == Pubkey::default(),
+            ErrorCode::CollateralMintMismatch
+        );
+        
+        // The underlying's quote currency names the collateral (e.g. USDC for AAPL/USDC),
+        // and its trusted feed replaces the SOL feed for every margin and settlement conversion
+        let collateral_feed = &ctx.accounts.collateral_price_feed;
+        require!(
+            collateral_feed.authority == ctx.accounts.oracle_config.authority,
+            ErrorCode::UntrustedPriceFeed
+        );
+        require!(
+            option.underlying.split('/').nth(1) == Some(collateral_feed.symbol.as_str()),
+            ErrorCode::PriceFeedMismatch
+        );
+        
+        option.collateral_mint = ctx.accounts.collateral_mint.key();
+        option.collateral_decimals = ctx.accounts.collateral_mint.decimals;
+        option.sol_price_feed = collateral_feed.key();
+        
+        msg!("Token vault created for collateral mint {}", option.collateral_mint);
+        
+        Ok(())
+    }
+
+    /// Purchase a token-collateralized option
+    /// Premium is paid to the seller and both margins are deposited in the vault
+    pub fn purchase_token_option(ctx: Context<PurchaseTokenOption>) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        // Read values we need before token transfers
+        let price = ctx.accounts.option.price;
+        let margin_amount = ctx.accounts.option.initial_margin;
+        let expiry = ctx.accounts.option.expiry_date;
+        let status = ctx.accounts.option.status.clone();
+        let is_test = ctx.accounts.option.is_test;
+        
+        require!(
+            status == OptionStatus::Listed,
+            ErrorCode::OptionNotAvailable
+        );
+        
+        require!(
+            ctx.accounts.seller.key() == ctx.accounts.option.seller,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            ctx.accounts.collateral_mint.key() == ctx.accounts.option.collateral_mint,
+            ErrorCode::CollateralMintMismatch
+        );
+        
+        // Only check expiry for production contracts
+        if !is_test {
+            require!(
+                clock.unix_timestamp < expiry,
+                ErrorCode::OptionExpired
+            );
+        }
+        
+        // Premium from buyer to seller
+        transfer_tokens(
+            &ctx.accounts.buyer_token_account,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.buyer.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            price,
+            &[],
+        )?;
+        
+        // Margins from both parties into the vault
+        transfer_tokens(
+            &ctx.accounts.buyer_token_account,
+            &ctx.accounts.vault,
+            &ctx.accounts.buyer.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            margin_amount,
+            &[],
+        )?;
+        transfer_tokens(
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.vault,
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            margin_amount,
+            &[],
+        )?;
+        
+        let option = &mut ctx.accounts.option;
+        option.status = OptionStatus::Owned;
+        option.owner = ctx.accounts.buyer.key();
+        option.seller_margin = margin_amount;
+        option.buyer_margin = margin_amount;
+        option.last_settlement_date = clock.unix_timestamp;
+        
+        Ok(())
+    }
+
+    /// Resell a token-collateralized option on the secondary market
+    /// Returns the previous owner's margin from the vault and collects the new buyer's margin
+    pub fn resell_token_option(ctx: Context<ResellTokenOption>, resell_price: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        // Read values before token transfers
+        let status = ctx.accounts.option.status.clone();
+        let owner = ctx.accounts.option.owner;
+        let expiry_date = ctx.accounts.option.expiry_date;
+        let old_buyer_margin = ctx.accounts.option.buyer_margin;
+        let initial_margin = ctx.accounts.option.initial_margin;
+        let is_test = ctx.accounts.option.is_test;
+        let seller = ctx.accounts.option.seller;
+        let series_id = ctx.accounts.option.series_id.to_le_bytes();
+        let bump = ctx.accounts.option.bump;
+        
+        require!(
+            status == OptionStatus::Owned,
+            ErrorCode::OptionNotAvailable
+        );
+        
+        require!(
+            ctx.accounts.current_owner.key() == owner,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            ctx.accounts.collateral_mint.key() == ctx.accounts.option.collateral_mint,
+            ErrorCode::CollateralMintMismatch
+        );
+        
+        // Only check expiry for production contracts
+        if !is_test {
+            require!(
+                clock.unix_timestamp < expiry_date,
+                ErrorCode::OptionExpired
+            );
+        }
+        
+        require!(resell_price > 0, ErrorCode::PriceMustBeNonZero);
+        
+        let signer_seeds: &[&[&[u8]]] = &[&[b"option", seller.as_ref(), &series_id, &[bump]]];
+        
+        // Step 1: Resell price from new buyer to current owner
+        transfer_tokens(
+            &ctx.accounts.new_buyer_token_account,
+            &ctx.accounts.current_owner_token_account,
+            &ctx.accounts.new_buyer.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            resell_price,
+            &[],
+        )?;
+        
+        // Step 2: Old buyer's margin from the vault to the previous owner
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.current_owner_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            old_buyer_margin,
+            signer_seeds,
+        )?;
+        
+        // Step 3: New buyer's margin into the vault
+        transfer_tokens(
+            &ctx.accounts.new_buyer_token_account,
+            &ctx.accounts.vault,
+            &ctx.accounts.new_buyer.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            initial_margin,
+            &[],
+        )?;
+        
+        let option = &mut ctx.accounts.option;
+        option.owner = ctx.accounts.new_buyer.key();
+        option.buyer_margin = initial_margin;
+        
+        msg!("Token option resold: margin {} returned to previous owner, margin {} collected from new buyer",
+             old_buyer_margin, initial_margin);
+        
+        Ok(())
+    }
+
+    /// Exercise a token-collateralized option according to its exercise style
+    /// Pays out of the vault like exercise_option, then closes the empty vault
+    /// and returns its rent to the seller
+    pub fn exercise_token_option(ctx: Context<ExerciseTokenOption>) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        // Read values before token transfers
+        let status = ctx.accounts.option.status.clone();
+        let owner = ctx.accounts.option.owner;
+        let seller = ctx.accounts.option.seller;
+        let option_type = ctx.accounts.option.option_type;
+        let strike = ctx.accounts.option.strike;
+        let buyer_margin = ctx.accounts.option.buyer_margin;
+        let seller_margin = ctx.accounts.option.seller_margin;
+        let expiry_price = ctx.accounts.option.expiry_price;
+        let accumulated_variation = ctx.accounts.option.accumulated_variation;
+        let series_id = ctx.accounts.option.series_id.to_le_bytes();
+        let bump = ctx.accounts.option.bump;
+        
+        require!(
+            status == OptionStatus::Owned,
+            ErrorCode::OptionNotOwned
+        );
+        
+        require!(
+            ctx.accounts.owner.key() == owner && ctx.accounts.seller.key() == seller,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            ctx.accounts.collateral_mint.key() == ctx.accounts.option.collateral_mint,
+            ErrorCode::CollateralMintMismatch
+        );
+        
+        require!(
+            ctx.accounts.asset_price_feed.key() == ctx.accounts.option.asset_price_feed
+                && ctx.accounts.sol_price_feed.key() == ctx.accounts.option.sol_price_feed,
+            ErrorCode::PriceFeedMismatch
+        );
+        
+        check_exercise_window(&ctx.accounts.option, clock.unix_timestamp)?;
+        
+        let final_ratio = if expiry_price > 0 {
+            expiry_price
+        } else {
+            let asset_price_usd = read_price_usd(&ctx.accounts.asset_price_feed, clock.unix_timestamp)?;
+            let collateral_price_usd = read_price_usd(&ctx.accounts.sol_price_feed, clock.unix_timestamp)?;
+            calculate_ratio(asset_price_usd, collateral_price_usd, ctx.accounts.option.collateral_decimals)?
+        };
+        
+        let settlement_value = calculate_settlement_value(
+            option_type,
+            final_ratio,
+            strike,
+            ctx.accounts.option.contract_size,
+            ctx.accounts.option.quantity,
+        )?;
+        let (owner_amount, seller_amount, shortfall) = calculate_exercise_split(
+            settlement_value,
+            accumulated_variation,
+            buyer_margin,
+            seller_margin,
+        )?;
+        
+        msg!("Token exercise settlement - Asset/collateral ratio: {}, Strike: {}, Settlement: {}, Variation already paid: {}",
+            final_ratio, strike, settlement_value, accumulated_variation);
+        
+        let signer_seeds: &[&[&[u8]]] = &[&[b"option", seller.as_ref(), &series_id, &[bump]]];
+        
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            owner_amount,
+            signer_seeds,
+        )?;
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            seller_amount,
+            signer_seeds,
+        )?;
+        
+        close_vault(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.seller_token_account.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer_seeds,
+        )?;
+        
+        if shortfall > 0 {
+            msg!("Seller margin exhausted - shortfall {}", shortfall);
+        }
+        
+        let option = &mut ctx.accounts.option;
+        option.status = OptionStatus::Expired;
+        option.last_settlement_price = final_ratio;
+        option.buyer_margin = 0;
+        option.seller_margin = 0;
+        option.settlement_shortfall = shortfall;
+        
+        Ok(())
+    }
+
+    /// Top up margin on a margin-called token option
+    /// Same rules as top_up_margin, with the deposit paid into the vault
+    pub fn top_up_token_margin(ctx: Context<TopUpTokenMargin>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        // Read values before token transfers
+        let status = ctx.accounts.option.status.clone();
+        let owner = ctx.accounts.option.owner;
+        let seller = ctx.accounts.option.seller;
+        let is_test = ctx.accounts.option.is_test;
+        let margin_call_date = ctx.accounts.option.margin_call_date;
+        let initial_margin = ctx.accounts.option.initial_margin;
+        let buyer_margin = ctx.accounts.option.buyer_margin;
+        let seller_margin = ctx.accounts.option.seller_margin;
+        
+        require!(
+            status == OptionStatus::MarginCalled,
+            ErrorCode::OptionNotMarginCalled
+        );
+        
+        require!(amount > 0, ErrorCode::MarginMustBeNonZero);
+        
+        require!(
+            ctx.accounts.collateral_mint.key() == ctx.accounts.option.collateral_mint,
+            ErrorCode::CollateralMintMismatch
+        );
+        
+        // Only enforce the grace window for production contracts
+        if !is_test {
+            require!(
+                clock.unix_timestamp < margin_call_date + MARGIN_CALL_GRACE_PERIOD,
+                ErrorCode::GracePeriodElapsed
+            );
+        }
+        
+        // Only the party whose margin fell to the threshold can cure the call
+        let depositor = ctx.accounts.depositor.key();
+        let is_buyer = if depositor == owner && buyer_margin < seller_margin {
+            true
+        } else if depositor == seller && seller_margin < buyer_margin {
+            false
+        } else {
+            return err!(ErrorCode::Unauthorized);
+        };
+        
+        transfer_tokens(
+            &ctx.accounts.depositor_token_account,
+            &ctx.accounts.vault,
+            &ctx.accounts.depositor.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            amount,
+            &[],
+        )?;
+        
+        let option = &mut ctx.accounts.option;
+        let new_margin = if is_buyer {
+            option.buyer_margin = buyer_margin
+                .checked_add(amount)
+                .ok_or(ErrorCode::CalculationOverflow)?;
+            option.buyer_margin
+        } else {
+            option.seller_margin = seller_margin
+                .checked_add(amount)
+                .ok_or(ErrorCode::CalculationOverflow)?;
+            option.seller_margin
+        };
+        
+        if new_margin >= initial_margin {
+            option.status = OptionStatus::Owned;
+            option.margin_call_date = 0;
+            msg!("Margin call cured - margin restored to {}", new_margin);
+        } else {
+            msg!("Margin topped up to {}, {} required to cure", new_margin, initial_margin);
+        }
+        
+        Ok(())
+    }
+
+    /// Close out a margin-called token option
+    /// Pays each party's margin out of the vault, then closes the vault and
+    /// the option account once the grace window has passed
+    pub fn close_token_margin_called(ctx: Context<CloseTokenMarginCalled>) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        // Read values before token transfers
+        let status = ctx.accounts.option.status.clone();
+        let owner = ctx.accounts.option.owner;
+        let seller = ctx.accounts.option.seller;
+        let is_test = ctx.accounts.option.is_test;
+        let margin_call_date = ctx.accounts.option.margin_call_date;
+        let buyer_margin = ctx.accounts.option.buyer_margin;
+        let seller_margin = ctx.accounts.option.seller_margin;
+        let final_price = ctx.accounts.option.last_settlement_price;
+        let series_id = ctx.accounts.option.series_id.to_le_bytes();
+        let bump = ctx.accounts.option.bump;
+        
+        require!(
+            status == OptionStatus::MarginCalled,
+            ErrorCode::OptionNotMarginCalled
+        );
+        
+        require!(
+            ctx.accounts.owner.key() == owner && ctx.accounts.seller.key() == seller,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            ctx.accounts.collateral_mint.key() == ctx.accounts.option.collateral_mint,
+            ErrorCode::CollateralMintMismatch
+        );
+        
+        // Give the under-margined party the full grace window to top up
+        if !is_test {
+            require!(
+                clock.unix_timestamp >= margin_call_date + MARGIN_CALL_GRACE_PERIOD,
+                ErrorCode::GracePeriodActive
+            );
+        }
+        
+        let signer_seeds: &[&[&[u8]]] = &[&[b"option", seller.as_ref(), &series_id, &[bump]]];
+        
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            buyer_margin,
+            signer_seeds,
+        )?;
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            seller_margin,
+            signer_seeds,
+        )?;
+        close_vault(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.seller_token_account.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer_seeds,
+        )?;
+        
+        msg!("Token margin call closed out - final settlement price: {}, buyer margin: {}, seller margin: {}",
+             final_price, buyer_margin, seller_margin);
+        
+        // Remaining rent is returned to the seller when the account is closed
+        let option = &mut ctx.accounts.option;
+        option.status = OptionStatus::Expired;
+        option.buyer_margin = 0;
+        option.seller_margin = 0;
+        
+        Ok(())
+    }
+
+    /// Automatically exercise an in-the-money token option after expiry (permissionless crank)
+    /// Same rules as auto_exercise, paid out of the vault; the keeper bounty is
+    /// denominated in the collateral mint and the empty vault is closed
+    pub fn auto_exercise_token_option(ctx: Context<AutoExerciseTokenOption>) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        // Read values before token transfers
+        let status = ctx.accounts.option.status.clone();
+        let expiry_date = ctx.accounts.option.expiry_date;
+        let expiry_price = ctx.accounts.option.expiry_price;
+        let option_type = ctx.accounts.option.option_type;
+        let strike = ctx.accounts.option.strike;
+        let seller = ctx.accounts.option.seller;
+        let buyer_margin = ctx.accounts.option.buyer_margin;
+        let seller_margin = ctx.accounts.option.seller_margin;
+        let keeper_bounty = ctx.accounts.option.keeper_bounty;
+        let accumulated_variation = ctx.accounts.option.accumulated_variation;
+        let series_id = ctx.accounts.option.series_id.to_le_bytes();
+        let bump = ctx.accounts.option.bump;
+        
+        require!(
+            status == OptionStatus::Owned,
+            ErrorCode::OptionNotOwned
+        );
+        
+        require!(
+            clock.unix_timestamp >= expiry_date,
+            ErrorCode::OptionNotExpired
+        );
+        
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.option.owner
+                && ctx.accounts.seller.key() == seller,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            ctx.accounts.collateral_mint.key() == ctx.accounts.option.collateral_mint,
+            ErrorCode::CollateralMintMismatch
+        );
+        
+        require!(
+            ctx.accounts.asset_price_feed.key() == ctx.accounts.option.asset_price_feed
+                && ctx.accounts.sol_price_feed.key() == ctx.accounts.option.sol_price_feed,
+            ErrorCode::PriceFeedMismatch
+        );
+        
+        // Settle at the price recorded at expiry if there is one, otherwise at the oracle price
+        let final_ratio = if expiry_price > 0 {
+            expiry_price
+        } else {
+            let asset_price_usd = read_price_usd(&ctx.accounts.asset_price_feed, clock.unix_timestamp)?;
+            let collateral_price_usd = read_price_usd(&ctx.accounts.sol_price_feed, clock.unix_timestamp)?;
+            calculate_ratio(asset_price_usd, collateral_price_usd, ctx.accounts.option.collateral_decimals)?
+        };
+        
+        let settlement_value = calculate_settlement_value(
+            option_type,
+            final_ratio,
+            strike,
+            ctx.accounts.option.contract_size,
+            ctx.accounts.option.quantity,
+        )?;
+        
+        require!(
+            settlement_value > keeper_bounty,
+            ErrorCode::BelowAutoExerciseThreshold
+        );
+        
+        let (owner_amount, seller_amount, shortfall) = calculate_exercise_split(
+            settlement_value,
+            accumulated_variation,
+            buyer_margin,
+            seller_margin,
+        )?;
+        let keeper_reward = keeper_bounty.min(owner_amount);
+        let owner_amount = owner_amount - keeper_reward;
+        
+        msg!("Token auto-exercise settlement - Asset/collateral ratio: {}, Strike: {}, Settlement: {}, Keeper reward: {}",
+            final_ratio, strike, settlement_value, keeper_reward);
+        
+        let signer_seeds: &[&[&[u8]]] = &[&[b"option", seller.as_ref(), &series_id, &[bump]]];
+        
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            owner_amount,
+            signer_seeds,
+        )?;
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            seller_amount,
+            signer_seeds,
+        )?;
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.keeper_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            keeper_reward,
+            signer_seeds,
+        )?;
+        close_vault(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.seller_token_account.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer_seeds,
+        )?;
+        
+        if shortfall > 0 {
+            msg!("Seller margin exhausted - shortfall {}", shortfall);
+        }
+        
+        let option = &mut ctx.accounts.option;
+        option.status = OptionStatus::Expired;
+        option.last_settlement_price = final_ratio;
+        option.buyer_margin = 0;
+        option.seller_margin = 0;
+        option.settlement_shortfall = shortfall;
+        
+        Ok(())
+    }
+
+    /// Expire an owned token option past its expiry date (permissionless crank)
+    /// Same rules as expire_option: out-of-the-money options are paid out of the
+    /// vault and in-the-money options record the expiry price, then settle at it
+    /// once EXERCISE_GRACE_PERIOD has passed. The vault is closed on payout.
+    /// Listed token options hold no collateral and are expired by expire_option
+    pub fn expire_token_option(ctx: Context<ExpireTokenOption>) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        // Read values before token transfers
+        let status = ctx.accounts.option.status.clone();
+        let expiry_date = ctx.accounts.option.expiry_date;
+        let expiry_price = ctx.accounts.option.expiry_price;
+        let seller = ctx.accounts.option.seller;
+        let series_id = ctx.accounts.option.series_id.to_le_bytes();
+        let bump = ctx.accounts.option.bump;
+        
+        require!(
+            status == OptionStatus::Owned,
+            ErrorCode::CannotExpireOption
+        );
+        
+        require!(
+            clock.unix_timestamp >= expiry_date,
+            ErrorCode::OptionNotExpired
+        );
+        
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.option.owner
+                && ctx.accounts.seller.key() == seller,
+            ErrorCode::Unauthorized
+        );
+        
+        require!(
+            ctx.accounts.collateral_mint.key() == ctx.accounts.option.collateral_mint,
+            ErrorCode::CollateralMintMismatch
+        );
+        
+        // An in-the-money option that has already had its expiry price recorded is
+        // left for its owner to exercise until the grace period ends
+        let final_ratio = if expiry_price > 0 {
+            require!(
+                clock.unix_timestamp >= expiry_date.saturating_add(EXERCISE_GRACE_PERIOD),
+                ErrorCode::AwaitingExercise
+            );
+            expiry_price
+        } else {
+            require!(
+                ctx.accounts.asset_price_feed.key() == ctx.accounts.option.asset_price_feed
+                    && ctx.accounts.sol_price_feed.key() == ctx.accounts.option.sol_price_feed,
+                ErrorCode::PriceFeedMismatch
+            );
+            
+            let asset_price_usd = read_price_usd(&ctx.accounts.asset_price_feed, clock.unix_timestamp)?;
+            let collateral_price_usd = read_price_usd(&ctx.accounts.sol_price_feed, clock.unix_timestamp)?;
+            calculate_ratio(asset_price_usd, collateral_price_usd, ctx.accounts.option.collateral_decimals)?
+        };
+        
+        let settlement_value = calculate_settlement_value(
+            ctx.accounts.option.option_type,
+            final_ratio,
+            ctx.accounts.option.strike,
+            ctx.accounts.option.contract_size,
+            ctx.accounts.option.quantity,
+        )?;
+        
+        if expiry_price == 0 && settlement_value > 0 {
+            // Leave in-the-money options for exercise at the recorded price
+            let option = &mut ctx.accounts.option;
+            option.expiry_price = final_ratio;
+            msg!("Token option expired in the money - Asset/collateral ratio: {}, Strike: {}, awaiting exercise",
+                 final_ratio, option.strike);
+            return Ok(());
+        }
+        
+        let (owner_amount, seller_amount, shortfall) = calculate_exercise_split(
+            settlement_value,
+            ctx.accounts.option.accumulated_variation,
+            ctx.accounts.option.buyer_margin,
+            ctx.accounts.option.seller_margin,
+        )?;
+        
+        let signer_seeds: &[&[&[u8]]] = &[&[b"option", seller.as_ref(), &series_id, &[bump]]];
+        
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            owner_amount,
+            signer_seeds,
+        )?;
+        transfer_tokens(
+            &ctx.accounts.vault,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.token_program,
+            seller_amount,
+            signer_seeds,
+        )?;
+        close_vault(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.seller_token_account.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.option.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer_seeds,
+        )?;
+        
+        if settlement_value > 0 {
+            msg!("Unexercised token option settled at expiry - Asset/collateral ratio: {}, Settlement: {}", final_ratio, settlement_value);
+        } else {
+            msg!("Token option expired out of the money - Asset/collateral ratio: {}, margins returned", final_ratio);
+        }
+        
+        if shortfall > 0 {
+            msg!("Seller margin exhausted - shortfall {}", shortfall);
+        }
+        
+        let option = &mut ctx.accounts.option;
+        option.status = OptionStatus::Expired;
+        option.last_settlement_price = final_ratio;
+        option.buyer_margin = 0;
+        option.seller_margin = 0;
+        option.settlement_shortfall = shortfall;
+        
+        Ok(())
+    }
+}
+
+// Helper function to calculate P&L for daily settlement
+// Marks the option to its intrinsic value through the shared settlement math
+fn calculate_pnl(
+    option_type: u8,
+    current_price: u64,
+    reference_price: u64,
+    strike_price: u64,
+    contract_size: u64,
+    quantity: u64,
+) -> Result<(u64, u64)> {
+    // Returns (buyer_gain, seller_gain)
+    let kind = OptionKind::from_u8(option_type).ok_or(ErrorCode::InvalidOptionType)?;
+    let gains = settlement::pnl(kind, current_price, reference_price, strike_price, contract_size, quantity)
+        .map_err(|_| ErrorCode::CalculationOverflow)?;
+    
+    Ok(gains)
+}
+
+// Helper function to calculate final settlement value
+// The per-unit intrinsic value is scaled by contract_size * quantity
+fn calculate_settlement_value(
+    option_type: u8,
+    final_price: u64,
+    strike_price: u64,
+    contract_size: u64,
+    quantity: u64,
+) -> Result<u64> {
+    let kind = OptionKind::from_u8(option_type).ok_or(ErrorCode::InvalidOptionType)?;
+    let value = settlement::settlement_value(kind, final_price, strike_price, contract_size, quantity)
+        .map_err(|_| ErrorCode::CalculationOverflow)?;
+    
+    Ok(value)
+}
+
+// Helper function to scale a per-unit amount by the contract's notional
+fn scale_by_notional(amount: u64, contract_size: u64, quantity: u64) -> Result<u64> {
+    let scaled = settlement::scale_by_notional(amount, contract_size, quantity)
+        .map_err(|_| ErrorCode::CalculationOverflow)?;
+    
+    Ok(scaled)
+}
+
+// Helper function to check that an option can be exercised now under its exercise style
+// Anything can be exercised after expiry; test mode contracts skip the check
+fn check_exercise_window(option: &OptionContract, now: i64) -> Result<()> {
+    if option.is_test || now >= option.expiry_date {
+        return Ok(());
+    }
+    
+    match &option.exercise_style {
+        ExerciseStyle::European => err!(ErrorCode::CannotExerciseBeforeExpiry),
+        ExerciseStyle::American => Ok(()),
+        ExerciseStyle::Bermudan { exercise_dates } => {
+            require!(
+                exercise_dates
+                    .iter()
+                    .any(|date| now >= *date && now < date + EXERCISE_WINDOW),
+                ErrorCode::NotAnExerciseDate
+            );
+            Ok(())
+        }
+    }
+}
+
+// Helper function to reject native SOL instructions on options collateralized by a token mint
+fn require_native_collateral(option: &OptionContract) -> Result<()> {
+    require!(
+        option.collateral_mint == Pubkey::default(),
+        ErrorCode::TokenCollateralOption
+    );
+    Ok(())
+}
+
+// Helper function to move collateral tokens with a Token or Token-2022 transfer
+// Transfers out of the vault are signed by the option PDA through signer_seeds
+fn transfer_tokens<'info>(
+    from: &impl ToAccountInfo<'info>,
+    to: &impl ToAccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    
+    let cpi_accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: authority.clone(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds),
+        amount,
+        mint.decimals,
+    )
+}
+
+// Helper function to close a token vault once its margins are paid out, returning its rent to destination
+// Any balance left over (tokens sent to the vault from outside) goes to the seller first,
+// since the token program only closes empty accounts. Signed by the option PDA through signer_seeds
+fn close_vault<'info>(
+    vault: &AccountInfo<'info>,
+    seller_token_account: &AccountInfo<'info>,
+    collateral_mint: &InterfaceAccount<'info, Mint>,
+    destination: &AccountInfo<'info>,
+    option: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    // Read from the account data so the margin transfers made earlier in the instruction are reflected
+    let remaining = TokenAccount::try_deserialize(&mut &vault.try_borrow_data()?[..])?.amount;
+    if remaining > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferChecked {
+                    from: vault.clone(),
+                    mint: collateral_mint.to_account_info(),
+                    to: seller_token_account.clone(),
+                    authority: option.clone(),
+                },
+                signer_seeds,
+            ),
+            remaining,
+            collateral_mint.decimals,
+        )?;
+    }
+    
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: vault.clone(),
+            destination: destination.clone(),
+            authority: option.clone(),
+        },
+        signer_seeds,
+    ))
+}
+
+// Helper function to split the margins at exercise
+// Returns (owner_amount, seller_amount, shortfall); see settlement::exercise_split
+fn calculate_exercise_split(
+    settlement_value: u64,
+    accumulated_variation: i64,
+    buyer_margin: u64,
+    seller_margin: u64,
+) -> Result<(u64, u64, u64)> {
+    let split = settlement::exercise_split(settlement_value, accumulated_variation, buyer_margin, seller_margin)
+        .map_err(|_| ErrorCode::CalculationOverflow)?;
+    
+    Ok(split)
+}
+
+// Helper function to calculate the asset value in collateral terms (base units per asset unit)
+// asset_value_in_collateral = (asset_price_usd * 10^decimals) / collateral_price_usd
+// For native SOL collateral decimals is 9, giving lamports per asset unit
+fn calculate_ratio(asset_price_usd: u64, sol_price_usd: u64, decimals: u8) -> Result<u64> {
+    require!(sol_price_usd > 0, ErrorCode::InvalidPrice);
+    // Shared with price_oracle so off-chain previews match settlement exactly
+    let ratio = option_math::ratio(asset_price_usd, sol_price_usd, decimals, Rounding::Down)
+        .map_err(|_| ErrorCode::CalculationOverflow)?;
+    
+    Ok(ratio)
+}
+
+// Helper function to read a fresh, tight price from a feed in USD with 6 decimals
+fn read_price_usd(feed: &PriceFeed, now: i64) -> Result<u64> {
+    require!(feed.price > 0, ErrorCode::InvalidPrice);
+    
+    require!(
+        now.saturating_sub(feed.publish_time) <= MAX_PRICE_AGE,
+        ErrorCode::StalePrice
+    );
+    
+    // confidence / price must not exceed MAX_CONFIDENCE_BPS
+    let price = feed.price as u128;
+    require!(
+        (feed.confidence as u128)
+            .checked_mul(10_000)
+            .ok_or(ErrorCode::CalculationOverflow)?
+            <= price * MAX_CONFIDENCE_BPS as u128,
+        ErrorCode::PriceConfidenceTooWide
+    );
+    
+    // Rescale from the feed's exponent to 6 decimals
+    let shift = feed.exponent + USD_DECIMALS;
+    let scale = 10u128
+        .checked_pow(shift.unsigned_abs())
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    let price_usd = if shift >= 0 {
+        price.checked_mul(scale).ok_or(ErrorCode::CalculationOverflow)?
+    } else {
+        price / scale
+    };
+    
+    u64::try_from(price_usd).map_err(|_| error!(ErrorCode::CalculationOverflow))
+}
+
+// Helper function to move lamports out of the program-owned option account
+fn release_lamports(
+    option: &AccountInfo,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    
+    let option_balance = option
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientMargin)?;
+    let recipient_balance = recipient
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    
+    **option.try_borrow_mut_lamports()? = option_balance;
+    **recipient.try_borrow_mut_lamports()? = recipient_balance;
+    
+    Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(option_type: u8, underlying: String)]
+#[instruction(series_id: u64)]
 pub struct InitializeOption<'info> {
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = 8 + SellerState::INIT_SPACE,
+        seeds = [b"seller", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_state: Account<'info, SellerState>,
     #[account(
         init,
         payer = seller,
         space = 8 + OptionContract::INIT_SPACE,
-        seeds = [b"option", seller.key().as_ref(), underlying.as_bytes()],
+        seeds = [b"option", seller.key().as_ref(), series_id.to_le_bytes().as_ref()],
         bump
     )]
     pub option: Account<'info, OptionContract>,
+    #[account(seeds = [b"oracle_config"], bump = oracle_config.bump)]
+    pub oracle_config: Account<'info, OracleConfig>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
     #[account(mut)]
     pub seller: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct InitializeOracleConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + OracleConfig::INIT_SPACE,
+        seeds = [b"oracle_config"],
+        bump
+    )]
+    pub oracle_config: Account<'info, OracleConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Escrow>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetOracleAuthority<'info> {
+    #[account(mut, seeds = [b"oracle_config"], bump = oracle_config.bump)]
+    pub oracle_config: Account<'info, OracleConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(symbol: String)]
+pub struct InitializePriceFeed<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [b"price_feed", authority.key().as_ref(), symbol.as_bytes()],
+        bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceFeed<'info> {
+    #[account(mut)]
+    pub price_feed: Account<'info, PriceFeed>,
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct PurchaseOption<'info> {
     #[account(mut)]
@@ -513,6 +2187,32 @@ pub struct PurchaseOption<'info> {
 
 #[derive(Accounts)]
 pub struct ExerciseOption<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
+}
+
+#[derive(Accounts)]
+pub struct AutoExercise<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetKeeperBounty<'info> {
     #[account(mut)]
     pub option: Account<'info, OptionContract>,
     pub owner: Signer<'info>,
@@ -522,6 +2222,20 @@ pub struct ExerciseOption<'info> {
 pub struct ExpireOption<'info> {
     #[account(mut)]
     pub option: Account<'info, OptionContract>,
+    /// Only required when an owned option expires out of the money
+    #[account(mut)]
+    pub owner: Option<SystemAccount<'info>>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
+}
+
+#[derive(Accounts)]
+pub struct QuotePremium<'info> {
+    pub option: Account<'info, OptionContract>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
 }
 
 #[derive(Accounts)]
@@ -531,6 +2245,246 @@ pub struct DelistOption<'info> {
     pub seller: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct CloseOption<'info> {
+    #[account(mut, close = seller)]
+    pub option: Account<'info, OptionContract>,
+    /// CHECK: Token vault PDA of the option; empty for native options and for
+    /// token options whose vault was closed at settlement
+    #[account(mut, seeds = [b"vault", option.key().as_ref()], bump)]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    /// The accounts below are only required while a token option's vault is still open
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+    /// Receives any tokens left in the vault
+    #[account(mut)]
+    pub seller_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTokenVault<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    #[account(mint::token_program = token_program)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"vault", option.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = option,
+        token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(seeds = [b"oracle_config"], bump = oracle_config.bump)]
+    pub oracle_config: Account<'info, OracleConfig>,
+    /// Prices the collateral mint; stored as the option's collateral feed
+    pub collateral_price_feed: Account<'info, PriceFeed>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseTokenOption<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"vault", option.key().as_ref()], bump)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = buyer,
+        token::token_program = token_program,
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = seller,
+        token::token_program = token_program,
+    )]
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub buyer: Signer<'info>,
+    pub seller: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ResellTokenOption<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"vault", option.key().as_ref()], bump)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = current_owner,
+        token::token_program = token_program,
+    )]
+    pub current_owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = new_buyer,
+        token::token_program = token_program,
+    )]
+    pub new_buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub current_owner: Signer<'info>,
+    pub new_buyer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExerciseTokenOption<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"vault", option.key().as_ref()], bump)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = seller,
+        token::token_program = token_program,
+    )]
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    /// Receives the vault's rent when it is closed
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpTokenMargin<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"vault", option.key().as_ref()], bump)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = depositor,
+        token::token_program = token_program,
+    )]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub depositor: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTokenMarginCalled<'info> {
+    #[account(mut, close = seller)]
+    pub option: Account<'info, OptionContract>,
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"vault", option.key().as_ref()], bump)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = seller,
+        token::token_program = token_program,
+    )]
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: SystemAccount<'info>,
+    /// Receives the vault's and the option account's rent
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AutoExerciseTokenOption<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"vault", option.key().as_ref()], bump)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = seller,
+        token::token_program = token_program,
+    )]
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = keeper,
+        token::token_program = token_program,
+    )]
+    pub keeper_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: SystemAccount<'info>,
+    /// Receives the vault's rent when it is closed
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
+    pub keeper: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireTokenOption<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"vault", option.key().as_ref()], bump)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = seller,
+        token::token_program = token_program,
+    )]
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: SystemAccount<'info>,
+    /// Receives the vault's rent when it is closed
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
 #[derive(Accounts)]
 pub struct ResellOption<'info> {
     #[account(mut)]
@@ -542,21 +2496,54 @@ pub struct ResellOption<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct TopUpMargin<'info> {
+    #[account(mut)]
+    pub option: Account<'info, OptionContract>,
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMarginCalled<'info> {
+    #[account(mut, close = seller)]
+    pub option: Account<'info, OptionContract>,
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+}
+
 #[derive(Accounts)]
 pub struct DailySettlement<'info> {
     #[account(mut)]
     pub option: Account<'info, OptionContract>,
+    pub asset_price_feed: Account<'info, PriceFeed>,
+    pub sol_price_feed: Account<'info, PriceFeed>,
     /// CHECK: Can be called by anyone to trigger settlement
     pub settler: Signer<'info>,
 }
 
+#[account]
+pub struct SellerState {
+    pub seller: Pubkey,            // 32 bytes
+    pub next_series_id: u64,       // 8 bytes - Series id for the seller's next option
+    pub bump: u8,                  // 1 byte
+}
+
+impl SellerState {
+    pub const INIT_SPACE: usize = 32 + 8 + 1;
+}
+
 #[account]
 pub struct OptionContract {
+    pub series_id: u64,            // 8 bytes - Per-seller series id used in the PDA seeds
     pub option_type: u8,           // 1 byte - 0: Call, 1: Put
     pub underlying: String,        // 4 + 32 bytes (max 32 chars)
     pub seller: Pubkey,            // 32 bytes
     pub initiation_date: i64,      // 8 bytes - immutable after initialization
-    pub expiry_date: i64,          // 8 bytes - immutable after initialization (30 days from initiation)
+    pub expiry_date: i64,          // 8 bytes - immutable after initialization
     pub status: OptionStatus,      // 1 byte
     pub price: u64,                // 8 bytes - Option premium
     pub strike: u64,               // 8 bytes - Strike price (asset/SOL ratio in lamports)
@@ -571,10 +2558,48 @@ pub struct OptionContract {
     pub buyer_margin: u64,         // 8 bytes - Current buyer margin balance
     pub last_settlement_date: i64, // 8 bytes - Last daily settlement timestamp
     pub last_settlement_price: u64,// 8 bytes - Last settled asset/SOL ratio
+    pub settlement_shortfall: u64, // 8 bytes - Intrinsic value the seller's margin could not cover at exercise
+    pub margin_call_date: i64,     // 8 bytes - When the margin call was triggered (0 if none)
+    pub asset_price_feed: Pubkey,  // 32 bytes - Price feed for the underlying asset in USD
+    pub sol_price_feed: Pubkey,    // 32 bytes - Price feed for SOL (or the collateral mint) in USD
+    pub expiry_price: u64,         // 8 bytes - Asset/SOL ratio recorded when an in-the-money option expires
+    pub keeper_bounty: u64,        // 8 bytes - Reward deducted from the payout for a keeper that auto-exercises
+    pub accumulated_variation: i64,// 8 bytes - Net variation margin moved to the buyer by daily settlement
+    pub exercise_style: ExerciseStyle, // 1 + 4 + 8 * MAX_EXERCISE_DATES bytes
+    pub collateral_mint: Pubkey,   // 32 bytes - Token collateral mint (default pubkey for native SOL)
+    pub collateral_decimals: u8,   // 1 byte - Decimals of the collateral, 9 for SOL
+    pub contract_size: u64,        // 8 bytes - Units of the underlying per contract
+    pub quantity: u64,             // 8 bytes - Number of contracts
 }
 
 impl OptionContract {
-    pub const INIT_SPACE: usize = 1 + (4 + 32) + 32 + 8 + 8 + 1 + 8 + 8 + 32 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8;
+    pub const INIT_SPACE: usize = 8 + 1 + (4 + 32) + 32 + 8 + 8 + 1 + 8 + 8 + 32 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 8 + 8 + 8 + (1 + 4 + 8 * MAX_EXERCISE_DATES) + 32 + 1 + 8 + 8;
+}
+
+#[account]
+pub struct PriceFeed {
+    pub authority: Pubkey,         // 32 bytes - Publisher allowed to push prices
+    pub symbol: String,            // 4 + 16 bytes (max 16 chars)
+    pub price: i64,                // 8 bytes - Price in USD scaled by 10^exponent
+    pub confidence: u64,           // 8 bytes - Confidence interval, same units as price
+    pub exponent: i32,             // 4 bytes - Power of ten applied to price and confidence
+    pub publish_time: i64,         // 8 bytes - Unix timestamp of the latest price
+    pub bump: u8,                  // 1 byte
+}
+
+impl PriceFeed {
+    pub const INIT_SPACE: usize = 32 + (4 + 16) + 8 + 8 + 4 + 8 + 1;
+}
+
+#[account]
+pub struct OracleConfig {
+    pub admin: Pubkey,             // 32 bytes - Upgrade authority that created the config
+    pub authority: Pubkey,         // 32 bytes - Publisher whose feeds options may settle against
+    pub bump: u8,                  // 1 byte
+}
+
+impl OracleConfig {
+    pub const INIT_SPACE: usize = 32 + 32 + 1;
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -586,6 +2611,13 @@ pub enum OptionStatus {
     MarginCalled, // Forcibly settled due to margin call (< 20% threshold)
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ExerciseStyle {
+    European,                                // Exercise on or after expiry only
+    American,                                // Exercise at any time while owned
+    Bermudan { exercise_dates: Vec<i64> },   // Exercise on listed dates or after expiry
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Unauthorized to perform this action")]
@@ -622,4 +2654,50 @@ pub enum ErrorCode {
     InsufficientMargin,
     #[msg("Initiation date cannot be in the past for production contracts")]
     InvalidInitiationDate,
+    #[msg("Series id does not match the seller's next series id")]
+    InvalidSeriesId,
+    #[msg("Expiry date must fall within the allowed tenor range")]
+    InvalidExpiryDate,
+    #[msg("Only listed or owned options can be expired")]
+    CannotExpireOption,
+    #[msg("Option expired in the money and is awaiting exercise")]
+    AwaitingExercise,
+    #[msg("Intrinsic value does not exceed the keeper bounty")]
+    BelowAutoExerciseThreshold,
+    #[msg("Option must be expired, delisted or margin called to close")]
+    OptionNotTerminal,
+    #[msg("Margins must be distributed before closing")]
+    MarginsNotDistributed,
+    #[msg("Option is not under a margin call")]
+    OptionNotMarginCalled,
+    #[msg("Margin call grace period has elapsed")]
+    GracePeriodElapsed,
+    #[msg("Margin call grace period is still active")]
+    GracePeriodActive,
+    #[msg("Price feed symbol too long (max 16 characters)")]
+    SymbolTooLong,
+    #[msg("Price exponent out of range")]
+    InvalidPriceExponent,
+    #[msg("Publish time is in the future or older than the current price")]
+    InvalidPublishTime,
+    #[msg("Price feed does not match the option contract")]
+    PriceFeedMismatch,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Option can only be exercised on one of its exercise dates")]
+    NotAnExerciseDate,
+    #[msg("Exercise dates must be ascending and fall between initiation and expiry")]
+    InvalidExerciseDates,
+    #[msg("Option uses token collateral; use the token instructions")]
+    TokenCollateralOption,
+    #[msg("Collateral mint does not match the option contract")]
+    CollateralMintMismatch,
+    #[msg("Contract size and quantity must be greater than zero")]
+    InvalidContractSize,
+    #[msg("Option pricing failed")]
+    PricingFailed,
+    #[msg("Price feed is not published by the configured oracle authority")]
+    UntrustedPriceFeed,
 }
\ No newline at end of file
@@ -2,6 +2,8 @@ import * as anchor from '@coral-xyz/anchor';
 import { AnchorProvider, Program, web3 } from '@coral-xyz/anchor';
 import { assert } from 'chai';
 import { Escrow } from '../target/types/escrow';
+import { configureOracleAuthority, createPriceFeed, publishPrice } from './utils/price_feed';
+import { findNextOptionPda } from './utils/option_pda';
 
 /**
  * Comprehensive AAPL Historical Options Test Suite
@@ -32,13 +34,27 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
     let buyer: web3.Keypair;
     let newBuyer: web3.Keypair;
     let optionPda: web3.PublicKey;
+    let seriesId: anchor.BN;
     
     const underlying = "AAPL/SOL";
     const CALL_OPTION = 0;
+    const EUROPEAN = { european: {} };
+    const ONE_UNIT = new anchor.BN(1); // Contract size and quantity of a single-unit contract
+    const THIRTY_DAYS = 30 * 24 * 60 * 60; // Standard tenor in seconds
+
+    // Oracle price feeds published by a local test authority
+    let publisher: web3.Keypair;
+    let assetFeed: web3.PublicKey;
+    let solFeed: web3.PublicKey;
+
+    const publishPrices = async (assetPrice: anchor.BN, solPrice: anchor.BN) => {
+        await publishPrice(program, publisher, assetFeed, assetPrice);
+        await publishPrice(program, publisher, solFeed, solPrice);
+    };
     
     // Historical dates (Unix timestamps)
     const initiationDate = new Date('2025-08-01T00:00:00Z').getTime() / 1000;
-    const expiryDate = initiationDate + (30 * 24 * 60 * 60); // 30 days later
+    const expiryDate = initiationDate + THIRTY_DAYS; // 30 days later
     
     // Contract parameters
     const optionPrice = new anchor.BN(2 * web3.LAMPORTS_PER_SOL); // 2 SOL premium
@@ -67,19 +83,22 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         seller = web3.Keypair.generate();
         buyer = web3.Keypair.generate();
         newBuyer = web3.Keypair.generate();
+        publisher = web3.Keypair.generate();
         
         // Fund test accounts
         await connection.requestAirdrop(seller.publicKey, 10 * web3.LAMPORTS_PER_SOL);
         await connection.requestAirdrop(buyer.publicKey, 10 * web3.LAMPORTS_PER_SOL);
         await connection.requestAirdrop(newBuyer.publicKey, 10 * web3.LAMPORTS_PER_SOL);
+        await connection.requestAirdrop(publisher.publicKey, 2 * web3.LAMPORTS_PER_SOL);
         
         await new Promise(resolve => setTimeout(resolve, 2000));
         
+        assetFeed = await createPriceFeed(program, publisher, "AAPL");
+        solFeed = await createPriceFeed(program, publisher, "SOL");
+        await configureOracleAuthority(program, publisher.publicKey);
+        
         // Calculate PDA
-        [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), seller.publicKey.toBuffer(), Buffer.from(underlying)],
-            program.programId
-        );
+        [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
     });
 
     it('Initializes AAPL Call option contract on Aug 1, 2025', async () => {
@@ -88,17 +107,24 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying,
                 new anchor.BN(initiationDate),
+                new anchor.BN(expiryDate),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // Test mode enabled
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([seller])
             .rpc();
@@ -174,11 +200,14 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         
         const optionBefore = await program.account.optionContract.fetch(optionPda);
         
+        await publishPrices(toPrice(aaplPrice), toPrice(solPrice));
         await program.methods
-            .dailySettlement(toPrice(aaplPrice), toPrice(solPrice))
+            .dailySettlement()
             .accountsPartial({
                 option: optionPda,
                 settler: settler.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([settler])
             .rpc();
@@ -207,11 +236,14 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         
         const optionBefore = await program.account.optionContract.fetch(optionPda);
         
+        await publishPrices(toPrice(aaplPrice), toPrice(solPrice));
         await program.methods
-            .dailySettlement(toPrice(aaplPrice), toPrice(solPrice))
+            .dailySettlement()
             .accountsPartial({
                 option: optionPda,
                 settler: settler.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([settler])
             .rpc();
@@ -235,11 +267,14 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         
         const optionBefore = await program.account.optionContract.fetch(optionPda);
         
+        await publishPrices(toPrice(aaplPrice), toPrice(solPrice));
         await program.methods
-            .dailySettlement(toPrice(aaplPrice), toPrice(solPrice))
+            .dailySettlement()
             .accountsPartial({
                 option: optionPda,
                 settler: settler.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([settler])
             .rpc();
@@ -301,11 +336,14 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         
         const optionBefore = await program.account.optionContract.fetch(optionPda);
         
+        await publishPrices(toPrice(aaplPrice), toPrice(solPrice));
         await program.methods
-            .dailySettlement(toPrice(aaplPrice), toPrice(solPrice))
+            .dailySettlement()
             .accountsPartial({
                 option: optionPda,
                 settler: settler.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([settler])
             .rpc();
@@ -334,11 +372,15 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         const buyerBalanceBefore = await connection.getBalance(newBuyer.publicKey);
         const sellerBalanceBefore = await connection.getBalance(seller.publicKey);
         
+        await publishPrices(toPrice(aaplPrice), toPrice(solPrice));
         await program.methods
-            .exerciseOption(toPrice(aaplPrice), toPrice(solPrice))
+            .exerciseOption()
             .accountsPartial({
                 option: optionPda,
                 owner: newBuyer.publicKey,
+                seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([newBuyer])
             .rpc();
@@ -352,6 +394,10 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         const isSettled = optionAfter.status.expired !== undefined || optionAfter.status.marginCalled !== undefined;
         assert.isTrue(isSettled, 'Option should be expired or margin called after exercise');
         
+        // Exercise releases every lamport of margin held by the option account
+        assert.equal(optionAfter.buyerMargin.toNumber(), 0);
+        assert.equal(optionAfter.sellerMargin.toNumber(), 0);
+        
         console.log('✅ Option exercised');
         console.log(`   Final buyer margin: ${optionAfter.buyerMargin.toNumber() / web3.LAMPORTS_PER_SOL} SOL`);
         console.log(`   Final seller margin: ${optionAfter.sellerMargin.toNumber() / web3.LAMPORTS_PER_SOL} SOL`);
@@ -364,7 +410,7 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         console.log('   Creating new contract with small margins for extreme volatility test');
         
         // Create a new contract for margin call test
-        const marginCallUnderlying = "AAPL-MARGIN-TEST/SOL";
+        const marginCallUnderlying = "AAPL/SOL";
         const smallMargin = new anchor.BN(0.1 * web3.LAMPORTS_PER_SOL); // 0.1 SOL - very small
         const smallPrice = new anchor.BN(0.2 * web3.LAMPORTS_PER_SOL);
         
@@ -375,27 +421,31 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         await connection.requestAirdrop(marginBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [marginPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), marginSeller.publicKey.toBuffer(), Buffer.from(marginCallUnderlying)],
-            program.programId
-        );
+        const [marginPda, seriesId] = await findNextOptionPda(program, marginSeller.publicKey);
         
         // Initialize with early August date
         const marginInitDate = new Date('2025-08-01T00:00:00Z').getTime() / 1000;
         
         await program.methods
             .initializeOption(
+                seriesId,
                 0, // CALL
                 marginCallUnderlying,
                 new anchor.BN(marginInitDate),
+                new anchor.BN(marginInitDate + THIRTY_DAYS),
                 smallPrice,
                 strikePrice,
                 smallMargin,
                 true,  // Test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: marginSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([marginSeller])
             .rpc();
@@ -425,21 +475,27 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
         let currentOption = await program.account.optionContract.fetch(marginPda);
         console.log(`   Initial: AAPL/SOL ratio = ${(225.5/150).toFixed(3)} (at strike 1.5)`);
         
+        await publishPrices(toPrice(225.50), toPrice(150.00));
         await program.methods
-            .dailySettlement(toPrice(225.50), toPrice(150.00))
+            .dailySettlement()
             .accountsPartial({
                 option: marginPda,
                 settler: settler.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([settler])
             .rpc();
         
         // Settlement 1: Moderate price increase (ratio = 1.67)
+        await publishPrices(toPrice(240.00), toPrice(144.00));
         await program.methods
-            .dailySettlement(toPrice(240.00), toPrice(144.00))
+            .dailySettlement()
             .accountsPartial({
                 option: marginPda,
                 settler: settler.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([settler])
             .rpc();
@@ -461,11 +517,14 @@ describe('AAPL Historical Options Contract (Aug 1 - Sep 1, 2025)', () => {
             console.log(`   Margins at ${marginPct}% of initial`);
             
             // Settlement 2: Larger increase (ratio = 1.88)
+            await publishPrices(toPrice(250.00), toPrice(133.00));
             await program.methods
-                .dailySettlement(toPrice(250.00), toPrice(133.00))
+                .dailySettlement()
                 .accountsPartial({
                     option: marginPda,
                     settler: settler.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([settler])
                 .rpc();
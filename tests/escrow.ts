@@ -2,6 +2,8 @@ import * as anchor from '@coral-xyz/anchor';
 import { AnchorProvider, Program, web3 } from '@coral-xyz/anchor';
 import { assert } from 'chai';
 import { Escrow } from '../target/types/escrow';
+import { configureOracleAuthority, createPriceFeed, publishPrice } from './utils/price_feed';
+import { findNextOptionPda, findOptionPda, findSellerStatePda } from './utils/option_pda';
 
 describe('Options Contract', () => {
     const provider = AnchorProvider.local();
@@ -13,46 +15,69 @@ describe('Options Contract', () => {
     let seller: web3.Keypair;
     let buyer: web3.Keypair;
     
-    const underlying = "SOL/USDC";
+    const underlying = "ASSET/USDC";
     const optionPrice = new anchor.BN(1 * web3.LAMPORTS_PER_SOL); // 1 SOL
     const strikePrice = new anchor.BN(150_000_000_000); // Strike: 150 SOL per asset unit
     const initialMargin = new anchor.BN(0.5 * web3.LAMPORTS_PER_SOL); // 0.5 SOL margin per party
     const CALL_OPTION = 0;
     const PUT_OPTION = 1;
+    const EUROPEAN = { european: {} };
+    const ONE_UNIT = new anchor.BN(1); // Contract size and quantity of a single-unit contract
+    const THIRTY_DAYS = 30 * 24 * 60 * 60; // Standard tenor in seconds
+
+    // Oracle price feeds published by a local test authority
+    let publisher: web3.Keypair;
+    let assetFeed: web3.PublicKey;
+    let solFeed: web3.PublicKey;
+
+    const publishPrices = async (assetPrice: anchor.BN, solPrice: anchor.BN) => {
+        await publishPrice(program, publisher, assetFeed, assetPrice);
+        await publishPrice(program, publisher, solFeed, solPrice);
+    };
 
     before(async () => {
         seller = web3.Keypair.generate();
         buyer = web3.Keypair.generate();
+        publisher = web3.Keypair.generate();
         
         // Airdrop SOL to users for testing
         await connection.requestAirdrop(seller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await connection.requestAirdrop(buyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await connection.requestAirdrop(publisher.publicKey, 2 * web3.LAMPORTS_PER_SOL);
         
         // Wait for airdrops to confirm
         await new Promise(resolve => setTimeout(resolve, 2000));
+        
+        assetFeed = await createPriceFeed(program, publisher, "ASSET");
+        solFeed = await createPriceFeed(program, publisher, "SOL");
+        await configureOracleAuthority(program, publisher.publicKey);
     });
 
     it('Initializes a Call option contract', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), seller.publicKey.toBuffer(), Buffer.from(underlying)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
 
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([seller])
             .rpc();
@@ -66,34 +91,38 @@ describe('Options Contract', () => {
         assert.equal(optionAccount.strike.toNumber(), strikePrice.toNumber());
         assert.equal(optionAccount.status.listed !== undefined, true);
         
-        // Verify expiry is 30 days from initiation
-        const expectedExpiry = currentTime + (30 * 24 * 60 * 60);
+        // Verify expiry is the requested 30-day tenor
+        const expectedExpiry = currentTime + THIRTY_DAYS;
         assert.equal(optionAccount.expiryDate.toNumber(), expectedExpiry);
     });
 
     it('Purchases an option contract', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying2 = "ETH/USDC";
+        const underlying2 = "ASSET/USDC";
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), seller.publicKey.toBuffer(), Buffer.from(underlying2)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
 
         // Initialize option
         await program.methods
             .initializeOption(
+                seriesId,
                 PUT_OPTION,
                 underlying2,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([seller])
             .rpc();
@@ -124,27 +153,31 @@ describe('Options Contract', () => {
 
     it('Prevents early exercise before expiry date', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying3 = "BTC/USDC";
+        const underlying3 = "ASSET/USDC";
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), seller.publicKey.toBuffer(), Buffer.from(underlying3)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
 
         // Initialize and purchase option (production mode to test early exercise prevention)
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying3,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 false,  // production mode - enforces European option rules
-                false   // allow_zero_margin
+                false,   // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([seller])
             .rpc();
@@ -164,11 +197,15 @@ describe('Options Contract', () => {
         const solPrice = new anchor.BN(50_000_000);    // $50 USD (6 decimals)
         
         try {
+            await publishPrices(assetPrice, solPrice);
             await program.methods
-                .exerciseOption(assetPrice, solPrice)
+                .exerciseOption()
                 .accountsPartial({
                     option: optionPda,
                     owner: buyer.publicKey,
+                    seller: seller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([buyer])
                 .rpc();
@@ -185,27 +222,31 @@ describe('Options Contract', () => {
 
     it('Verifies margin deposits after purchase', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying5 = "AVAX/USDC";
+        const underlying5 = "ASSET/USDC";
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), seller.publicKey.toBuffer(), Buffer.from(underlying5)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
 
         // Initialize option
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying5,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([seller])
             .rpc();
@@ -237,27 +278,31 @@ describe('Options Contract', () => {
 
     it('Delists an unsold option', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying4 = "MATIC/USDC";
+        const underlying4 = "ASSET/USDC";
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), seller.publicKey.toBuffer(), Buffer.from(underlying4)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
 
         // Initialize option
         await program.methods
             .initializeOption(
+                seriesId,
                 PUT_OPTION,
                 underlying4,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([seller])
             .rpc();
@@ -276,9 +321,129 @@ describe('Options Contract', () => {
         assert.equal(optionAccount.status.delisted !== undefined, true);
     });
 
+    it('Closes a delisted option and refunds rent to the seller', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const underlying_close = "ASSET/USDC";
+        
+        const [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
+
+        await program.methods
+            .initializeOption(
+                seriesId,
+                CALL_OPTION,
+                underlying_close,
+                new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
+                optionPrice,
+                strikePrice,
+                initialMargin,
+                true,  // is_test mode
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
+            )
+            .accountsPartial({
+                seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([seller])
+            .rpc();
+
+        await program.methods
+            .delistOption()
+            .accountsPartial({
+                option: optionPda,
+                seller: seller.publicKey,
+            })
+            .signers([seller])
+            .rpc();
+
+        const rent = await connection.getBalance(optionPda);
+        const sellerBalanceBefore = await connection.getBalance(seller.publicKey);
+
+        await program.methods
+            .closeOption()
+            .accountsPartial({
+                option: optionPda,
+                seller: seller.publicKey,
+                tokenProgram: null,
+                collateralMint: null,
+                sellerTokenAccount: null,
+            })
+            .signers([seller])
+            .rpc();
+
+        const sellerBalanceAfter = await connection.getBalance(seller.publicKey);
+        const closed = await connection.getAccountInfo(optionPda);
+        
+        assert.isNull(closed);
+        assert.isTrue(sellerBalanceAfter > sellerBalanceBefore + rent - 10_000, 'Seller should reclaim rent');
+    });
+
+    it('Prevents closing an owned option', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const underlying_noclose = "ASSET/USDC";
+        
+        const [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
+
+        await program.methods
+            .initializeOption(
+                seriesId,
+                CALL_OPTION,
+                underlying_noclose,
+                new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
+                optionPrice,
+                strikePrice,
+                initialMargin,
+                true,  // is_test mode
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
+            )
+            .accountsPartial({
+                seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([seller])
+            .rpc();
+
+        await program.methods
+            .purchaseOption()
+            .accountsPartial({
+                option: optionPda,
+                buyer: buyer.publicKey,
+                seller: seller.publicKey,
+            })
+            .signers([buyer, seller])
+            .rpc();
+
+        try {
+            await program.methods
+                .closeOption()
+                .accountsPartial({
+                    option: optionPda,
+                    seller: seller.publicKey,
+                    tokenProgram: null,
+                    collateralMint: null,
+                    sellerTokenAccount: null,
+                })
+                .signers([seller])
+                .rpc();
+            
+            assert.fail("Should have thrown error for closing an owned option");
+        } catch (error: any) {
+            assert.include(error.toString(), "OptionNotTerminal");
+        }
+    });
+
     it('Resells an option to a new buyer', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying9 = "UNI/USDC";
+        const underlying9 = "ASSET/USDC";
         
         // Use fresh keypairs with adequate funds
         const freshSeller = web3.Keypair.generate();
@@ -288,25 +453,29 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying9)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize option
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying9,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -361,7 +530,7 @@ describe('Options Contract', () => {
 
     it('Prevents unauthorized resell', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying10 = "AAVE/USDC";
+        const underlying10 = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         const freshBuyer = web3.Keypair.generate();
@@ -372,25 +541,29 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(unauthorized.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying10)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize and purchase option
         await program.methods
             .initializeOption(
+                seriesId,
                 PUT_OPTION,
                 underlying10,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -431,7 +604,7 @@ describe('Options Contract', () => {
 
     it('Verifies new buyer can afford resell premium and margin', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying_afford = "AFFORD/USDC";
+        const underlying_afford = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         const freshBuyer = web3.Keypair.generate();
@@ -445,25 +618,29 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(newBuyer.publicKey, totalRequired);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying_afford)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize and purchase option
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying_afford,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -521,7 +698,7 @@ describe('Options Contract', () => {
 
     it('Fails resell when new buyer cannot afford premium and margin', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying_poor = "POOR/USDC";
+        const underlying_poor = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         const freshBuyer = web3.Keypair.generate();
@@ -533,26 +710,30 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(poorBuyer.publicKey, 1.6 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying_poor)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize and purchase option with high margin requirement
         const highMargin = new anchor.BN(1 * web3.LAMPORTS_PER_SOL); // 1 SOL margin
         await program.methods
             .initializeOption(
+                seriesId,
                 PUT_OPTION,
                 underlying_poor,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 highMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -596,7 +777,7 @@ describe('Options Contract', () => {
 
     it('Rejects purchase attempt on delisted option', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying6 = "ADA/USDC";
+        const underlying6 = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         const freshBuyer = web3.Keypair.generate();
@@ -605,25 +786,29 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying6)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize and immediately delist the option
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying6,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -657,7 +842,7 @@ describe('Options Contract', () => {
 
     it('Rejects option initialization with zero price', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying7 = "DOT/USDC";
+        const underlying7 = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
@@ -667,20 +852,28 @@ describe('Options Contract', () => {
         const smallMargin = new anchor.BN(0.1 * web3.LAMPORTS_PER_SOL);
 
         // Attempt to initialize with zero price (should fail)
+        const [, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
         try {
             await program.methods
                 .initializeOption(
+                    seriesId,
                     PUT_OPTION,
                     underlying7,
                     new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
                     zeroPrice,  // Zero price - should fail
                     strikePrice,
                     smallMargin,
                     true,  // is_test mode
-                    false  // allow_zero_margin
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
                 )
                 .accountsPartial({
                     seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([freshSeller])
                 .rpc();
@@ -693,7 +886,7 @@ describe('Options Contract', () => {
 
     it('Rejects option initialization with zero strike price', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying8 = "LINK/USDC";
+        const underlying8 = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
@@ -703,20 +896,28 @@ describe('Options Contract', () => {
         const zeroStrike = new anchor.BN(0); // Invalid: zero strike
 
         // Attempt to initialize with zero strike price (should fail)
+        const [, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
         try {
             await program.methods
                 .initializeOption(
+                    seriesId,
                     CALL_OPTION,
                     underlying8,
                     new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
                     smallPrice,
                     zeroStrike,  // Zero strike - should fail
                     initialMargin,
                     true,  // is_test mode
-                    false  // allow_zero_margin
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
                 )
                 .accountsPartial({
                     seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([freshSeller])
                 .rpc();
@@ -729,31 +930,35 @@ describe('Options Contract', () => {
 
     it('Allows past dates for test contracts', async () => {
         const pastTime = Math.floor(Date.now() / 1000) - (10 * 24 * 60 * 60); // 10 days ago
-        const underlying11 = "TEST/USDC";
+        const underlying11 = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying11)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize test contract with past date (should succeed)
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying11,
                 new anchor.BN(pastTime),
+                new anchor.BN(pastTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode - allows past dates
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -765,27 +970,35 @@ describe('Options Contract', () => {
 
     it('Prevents past dates for production contracts', async () => {
         const pastTime = Math.floor(Date.now() / 1000) - (10 * 24 * 60 * 60); // 10 days ago
-        const underlying12 = "PROD/USDC";
+        const underlying12 = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
 
         // Try to initialize production contract with past date (should fail)
+        const [, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
         try {
             await program.methods
                 .initializeOption(
+                    seriesId,
                     CALL_OPTION,
                     underlying12,
                     new anchor.BN(pastTime),
+                    new anchor.BN(pastTime + THIRTY_DAYS),
                     optionPrice,
                     strikePrice,
                     initialMargin,
                     false,  // production mode - rejects past dates
-                    false   // allow_zero_margin
+                    false,   // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
                 )
                 .accountsPartial({
                     seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([freshSeller])
                 .rpc();
@@ -798,27 +1011,35 @@ describe('Options Contract', () => {
 
     it('Rejects invalid option type (not 0 or 1)', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying_invalid = "INVALID/USDC";
+        const underlying_invalid = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
 
         // Attempt to initialize with invalid option type (should fail)
+        const [, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
         try {
             await program.methods
                 .initializeOption(
+                    seriesId,
                     5,  // Invalid option type (should be 0 or 1)
                     underlying_invalid,
                     new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
                     optionPrice,
                     strikePrice,
                     initialMargin,
                     true,  // is_test mode
-                    false  // allow_zero_margin
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
                 )
                 .accountsPartial({
                     seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([freshSeller])
                 .rpc();
@@ -829,9 +1050,179 @@ describe('Options Contract', () => {
         }
     });
 
+    it('Creates several options on the same underlying for one seller', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const underlying_series = "ASSET/USDC";
+        const strikes = [strikePrice, strikePrice.muln(2)];
+        
+        const freshSeller = web3.Keypair.generate();
+        await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await new Promise(resolve => setTimeout(resolve, 1000));
+        
+        const created: web3.PublicKey[] = [];
+        for (const strike of strikes) {
+            const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
+            
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    underlying_series,
+                    new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
+                    optionPrice,
+                    strike,
+                    initialMargin,
+                    true,  // is_test mode
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([freshSeller])
+                .rpc();
+            
+            created.push(optionPda);
+        }
+        
+        // Seller's contracts are enumerable from series 0 up to the counter
+        const sellerState = await program.account.sellerState.fetch(
+            findSellerStatePda(program, freshSeller.publicKey)
+        );
+        assert.equal(sellerState.nextSeriesId.toNumber(), strikes.length);
+        
+        for (let i = 0; i < strikes.length; i++) {
+            const expectedPda = findOptionPda(program, freshSeller.publicKey, new anchor.BN(i));
+            assert.equal(expectedPda.toString(), created[i].toString());
+            
+            const optionAccount = await program.account.optionContract.fetch(expectedPda);
+            assert.equal(optionAccount.seriesId.toNumber(), i);
+            assert.equal(optionAccount.strike.toNumber(), strikes[i].toNumber());
+        }
+    });
+
+    it('Rejects a series id that skips the seller counter', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const [, nextSeriesId] = await findNextOptionPda(program, seller.publicKey);
+        
+        try {
+            await program.methods
+                .initializeOption(
+                    nextSeriesId.addn(1),
+                    CALL_OPTION,
+                    "ASSET/USDC",
+                    new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
+                    optionPrice,
+                    strikePrice,
+                    initialMargin,
+                    true,  // is_test mode
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: seller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([seller])
+                .rpc();
+            
+            assert.fail("Should have thrown error for skipped series id");
+        } catch (error: any) {
+            assert.include(error.toString(), "InvalidSeriesId");
+        }
+    });
+
+    it('Accepts a same-day (0DTE) expiry', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const underlying_0dte = "ASSET/USDC";
+        const sameDayExpiry = currentTime + 4 * 60 * 60; // 4 hours
+        
+        const [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
+
+        await program.methods
+            .initializeOption(
+                seriesId,
+                CALL_OPTION,
+                underlying_0dte,
+                new anchor.BN(currentTime),
+                new anchor.BN(sameDayExpiry),
+                optionPrice,
+                strikePrice,
+                initialMargin,
+                true,  // is_test mode
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
+            )
+            .accountsPartial({
+                seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([seller])
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        assert.equal(optionAccount.expiryDate.toNumber(), sameDayExpiry);
+    });
+
+    it('Rejects expiry outside the allowed tenor range', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const invalidExpiries = [
+            currentTime + 60,                       // Shorter than the minimum tenor
+            currentTime + 400 * 24 * 60 * 60,       // Longer than the maximum tenor
+            currentTime - 24 * 60 * 60,             // Before initiation
+        ];
+        
+        for (const [i, expiry] of invalidExpiries.entries()) {
+            const underlying_tenor = "ASSET/USDC";
+            
+            const [, seriesId] = await findNextOptionPda(program, seller.publicKey);
+            try {
+                await program.methods
+                    .initializeOption(
+                        seriesId,
+                        CALL_OPTION,
+                        underlying_tenor,
+                        new anchor.BN(currentTime),
+                        new anchor.BN(expiry),
+                        optionPrice,
+                        strikePrice,
+                        initialMargin,
+                        true,  // is_test mode
+                        false,  // allow_zero_margin
+                        EUROPEAN,  // exercise_style
+                        ONE_UNIT,  // contract_size
+                        ONE_UNIT   // quantity
+                    )
+                    .accountsPartial({
+                        seller: seller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
+                    })
+                    .signers([seller])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for invalid expiry");
+            } catch (error: any) {
+                assert.include(error.toString(), "InvalidExpiryDate");
+            }
+        }
+    });
+
     it('Prevents non-owner from exercising option', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying_notowner = "NOTOWNER/USDC";
+        const underlying_notowner = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         const freshBuyer = web3.Keypair.generate();
@@ -842,25 +1233,29 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(unauthorized.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying_notowner)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize and purchase option
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying_notowner,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -880,11 +1275,15 @@ describe('Options Contract', () => {
         const solPrice = new anchor.BN(50_000_000);
         
         try {
+            await publishPrices(assetPrice, solPrice);
             await program.methods
-                .exerciseOption(assetPrice, solPrice)
+                .exerciseOption()
                 .accountsPartial({
                     option: optionPda,
                     owner: unauthorized.publicKey,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([unauthorized])
                 .rpc();
@@ -897,7 +1296,7 @@ describe('Options Contract', () => {
 
     it('Prevents delist of owned option', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying_owned = "OWNED/USDC";
+        const underlying_owned = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         const freshBuyer = web3.Keypair.generate();
@@ -906,25 +1305,29 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying_owned)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize and purchase option
         await program.methods
             .initializeOption(
+                seriesId,
                 PUT_OPTION,
                 underlying_owned,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -956,9 +1359,9 @@ describe('Options Contract', () => {
         }
     });
 
-    it('Prevents settlement with zero SOL price', async () => {
+    it('Rejects a zero SOL price before settlement can read it', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying_settlement = "SETTLE/USDC";
+        const underlying_settlement = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         const freshBuyer = web3.Keypair.generate();
@@ -967,25 +1370,29 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying_settlement)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize and purchase option
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying_settlement,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -1000,33 +1407,45 @@ describe('Options Contract', () => {
             .signers([freshBuyer, freshSeller])
             .rpc();
 
-        // Attempt settlement with zero SOL price (should fail)
-        const zeroPrice = new anchor.BN(0);
         const validAssetPrice = new anchor.BN(100_000_000);
-        
-        const settler = web3.Keypair.generate();
-        await connection.requestAirdrop(settler.publicKey, 1 * web3.LAMPORTS_PER_SOL);
-        await new Promise(resolve => setTimeout(resolve, 1000));
+        const validSolPrice = new anchor.BN(100_000_000);
+        await publishPrices(validAssetPrice, validSolPrice);
 
+        // The feed refuses a zero price, so it keeps the last valid one
         try {
-            await program.methods
-                .dailySettlement(validAssetPrice, zeroPrice)
-                .accountsPartial({
-                    option: optionPda,
-                    settler: settler.publicKey,
-                })
-                .signers([settler])
-                .rpc();
+            await publishPrice(program, publisher, solFeed, new anchor.BN(0));
             
             assert.fail("Should have thrown error for zero SOL price");
         } catch (error: any) {
             assert.include(error.toString(), "InvalidPrice");
         }
+        
+        const feedAccount = await program.account.priceFeed.fetch(solFeed);
+        assert.equal(feedAccount.price.toNumber(), validSolPrice.toNumber());
+        
+        const settler = web3.Keypair.generate();
+        await connection.requestAirdrop(settler.publicKey, 1 * web3.LAMPORTS_PER_SOL);
+        await new Promise(resolve => setTimeout(resolve, 1000));
+
+        // Settlement reads the last valid prices: $100 / $100 = 1 SOL
+        await program.methods
+            .dailySettlement()
+            .accountsPartial({
+                option: optionPda,
+                settler: settler.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([settler])
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        assert.equal(optionAccount.lastSettlementPrice.toNumber(), web3.LAMPORTS_PER_SOL);
     });
 
     it('Prevents purchase of already owned option', async () => {
         const currentTime = Math.floor(Date.now() / 1000);
-        const underlying_double = "DOUBLE/USDC";
+        const underlying_double = "ASSET/USDC";
         
         const freshSeller = web3.Keypair.generate();
         const freshBuyer1 = web3.Keypair.generate();
@@ -1037,25 +1456,29 @@ describe('Options Contract', () => {
         await connection.requestAirdrop(freshBuyer2.publicKey, 5 * web3.LAMPORTS_PER_SOL);
         await new Promise(resolve => setTimeout(resolve, 1000));
         
-        const [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying_double)],
-            program.programId
-        );
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
         // Initialize option
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying_double,
                 new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // is_test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([freshSeller])
             .rpc();
@@ -1089,49 +1512,1341 @@ describe('Options Contract', () => {
         }
     });
 
-    // Zero Margin Tests
-    describe('Zero Margin Options (Test Mode)', () => {
-        it('Allows zero margin in test mode with allow_zero_margin=true', async () => {
-            const currentTime = Math.floor(Date.now() / 1000);
-            const underlying13 = "ZERO1/USDC";
-            const zeroMargin = new anchor.BN(0); // Zero margin
-            
-            const freshSeller = web3.Keypair.generate();
-            await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
-            await new Promise(resolve => setTimeout(resolve, 1000));
-            
-            const [optionPda] = web3.PublicKey.findProgramAddressSync(
-                [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying13)],
-                program.programId
-            );
-
-            // Initialize with zero margin and allow_zero_margin=true (should succeed)
-            await program.methods
-                .initializeOption(
-                    CALL_OPTION,
-                    underlying13,
-                    new anchor.BN(currentTime),
-                    optionPrice,
-                    strikePrice,
-                    zeroMargin,
-                    true,  // is_test mode
-                    true   // allow_zero_margin
-                )
-                .accountsPartial({
-                    seller: freshSeller.publicKey,
-                })
-                .signers([freshSeller])
-                .rpc();
+    it('Pays intrinsic value from seller margin on exercise', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const underlying_itm = "ASSET/USDC";
+        const lowStrike = new anchor.BN(2_000_000_000); // Strike: 2 SOL per asset unit
+        
+        const freshSeller = web3.Keypair.generate();
+        const freshBuyer = web3.Keypair.generate();
+        
+        await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await new Promise(resolve => setTimeout(resolve, 1000));
+        
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
-            const optionAccount = await program.account.optionContract.fetch(optionPda);
-            assert.equal(optionAccount.initialMargin.toNumber(), 0);
-            assert.equal(optionAccount.allowZeroMargin, true);
-            assert.equal(optionAccount.isTest, true);
-        });
+        await program.methods
+            .initializeOption(
+                seriesId,
+                CALL_OPTION,
+                underlying_itm,
+                new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
+                optionPrice,
+                lowStrike,
+                initialMargin,
+                true,  // is_test mode
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
+            )
+            .accountsPartial({
+                seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([freshSeller])
+            .rpc();
+
+        await program.methods
+            .purchaseOption()
+            .accountsPartial({
+                option: optionPda,
+                buyer: freshBuyer.publicKey,
+                seller: freshSeller.publicKey,
+            })
+            .signers([freshBuyer, freshSeller])
+            .rpc();
+
+        const buyerBalanceBefore = await connection.getBalance(freshBuyer.publicKey);
+        const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+        const pdaBalanceBefore = await connection.getBalance(optionPda);
+
+        // Ratio 2.1 SOL vs strike 2 SOL -> 0.1 SOL intrinsic value
+        const assetPrice = new anchor.BN(210_000_000); // $210 USD (6 decimals)
+        const solPrice = new anchor.BN(100_000_000);   // $100 USD (6 decimals)
+        const intrinsic = 100_000_000;
+
+        await publishPrices(assetPrice, solPrice);
+        await program.methods
+            .exerciseOption()
+            .accountsPartial({
+                option: optionPda,
+                owner: freshBuyer.publicKey,
+                seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([freshBuyer])
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        const buyerBalanceAfter = await connection.getBalance(freshBuyer.publicKey);
+        const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+        const pdaBalanceAfter = await connection.getBalance(optionPda);
+
+        assert.equal(optionAccount.status.expired !== undefined, true);
+        assert.equal(optionAccount.buyerMargin.toNumber(), 0);
+        assert.equal(optionAccount.sellerMargin.toNumber(), 0);
+        assert.equal(optionAccount.settlementShortfall.toNumber(), 0);
+
+        // Seller gets back margin minus intrinsic value, buyer gets margin plus intrinsic value (less fees)
+        assert.equal(sellerBalanceAfter - sellerBalanceBefore, initialMargin.toNumber() - intrinsic);
+        assert.isTrue(buyerBalanceAfter - buyerBalanceBefore > initialMargin.toNumber() + intrinsic - 10_000);
+        assert.equal(pdaBalanceBefore - pdaBalanceAfter, initialMargin.toNumber() * 2);
+    });
+
+    it('Scales the exercise payout by contract size and quantity', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const lowStrike = new anchor.BN(2_000_000_000); // Strike: 2 SOL per asset unit
+        const contractSize = new anchor.BN(2);
+        const quantity = new anchor.BN(3);
+        
+        const freshSeller = web3.Keypair.generate();
+        const freshBuyer = web3.Keypair.generate();
+        
+        await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await new Promise(resolve => setTimeout(resolve, 1000));
+        
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
+
+        await program.methods
+            .initializeOption(
+                seriesId,
+                CALL_OPTION,
+                "ASSET/USDC",
+                new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
+                optionPrice,
+                lowStrike,
+                initialMargin,
+                true,  // is_test mode
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                contractSize,
+                quantity
+            )
+            .accountsPartial({
+                seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([freshSeller])
+            .rpc();
+
+        await program.methods
+            .purchaseOption()
+            .accountsPartial({
+                option: optionPda,
+                buyer: freshBuyer.publicKey,
+                seller: freshSeller.publicKey,
+            })
+            .signers([freshBuyer, freshSeller])
+            .rpc();
+
+        const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+
+        // Ratio 2.01 SOL vs strike 2 SOL -> 0.01 SOL per unit, 6 units in total
+        await publishPrices(new anchor.BN(201_000_000), new anchor.BN(100_000_000));
+        await program.methods
+            .exerciseOption()
+            .accountsPartial({
+                option: optionPda,
+                owner: freshBuyer.publicKey,
+                seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([freshBuyer])
+            .rpc();
+
+        const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+        assert.equal(sellerBalanceAfter - sellerBalanceBefore, initialMargin.toNumber() - 60_000_000);
+    });
+
+    it('Rejects a zero contract size', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const [, seriesId] = await findNextOptionPda(program, seller.publicKey);
+
+        try {
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    "ASSET/USDC",
+                    new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
+                    optionPrice,
+                    strikePrice,
+                    initialMargin,
+                    true,  // is_test mode
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    new anchor.BN(0),  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: seller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([seller])
+                .rpc();
+            
+            assert.fail("Should have thrown error for zero contract size");
+        } catch (error: any) {
+            assert.include(error.toString(), "InvalidContractSize");
+        }
+    });
+
+    it('Bounds the buyer loss when an out-of-the-money call falls further', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const lowStrike = new anchor.BN(2_000_000_000); // Strike: 2 SOL per asset unit
+        
+        const freshSeller = web3.Keypair.generate();
+        const freshBuyer = web3.Keypair.generate();
+        
+        await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await new Promise(resolve => setTimeout(resolve, 1000));
+        
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
+
+        await program.methods
+            .initializeOption(
+                seriesId,
+                CALL_OPTION,
+                "ASSET/USDC",
+                new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
+                optionPrice,
+                lowStrike,
+                initialMargin,
+                true,  // is_test mode
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
+            )
+            .accountsPartial({
+                seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([freshSeller])
+            .rpc();
+
+        await program.methods
+            .purchaseOption()
+            .accountsPartial({
+                option: optionPda,
+                buyer: freshBuyer.publicKey,
+                seller: freshSeller.publicKey,
+            })
+            .signers([freshBuyer, freshSeller])
+            .rpc();
+
+        // Ratio 2.1 SOL: the call gains 0.1 SOL of intrinsic value
+        await publishPrices(new anchor.BN(210_000_000), new anchor.BN(100_000_000));
+        await program.methods
+            .dailySettlement()
+            .accountsPartial({
+                option: optionPda,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .rpc();
+
+        // Ratio 1.5 SOL: far below strike, the buyer only gives back the 0.1 SOL gained
+        await publishPrices(new anchor.BN(150_000_000), new anchor.BN(100_000_000));
+        await program.methods
+            .dailySettlement()
+            .accountsPartial({
+                option: optionPda,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        assert.equal(optionAccount.status.owned !== undefined, true);
+        assert.equal(optionAccount.buyerMargin.toNumber(), initialMargin.toNumber());
+        assert.equal(optionAccount.sellerMargin.toNumber(), initialMargin.toNumber());
+        assert.equal(optionAccount.accumulatedVariation.toNumber(), 0);
+    });
+
+    it('Records a shortfall when intrinsic value exceeds seller margin', async () => {
+        const currentTime = Math.floor(Date.now() / 1000);
+        const underlying_short = "ASSET/USDC";
+        const lowStrike = new anchor.BN(2_000_000_000); // Strike: 2 SOL per asset unit
+        
+        const freshSeller = web3.Keypair.generate();
+        const freshBuyer = web3.Keypair.generate();
+        
+        await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+        await new Promise(resolve => setTimeout(resolve, 1000));
+        
+        const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
+
+        await program.methods
+            .initializeOption(
+                seriesId,
+                CALL_OPTION,
+                underlying_short,
+                new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
+                optionPrice,
+                lowStrike,
+                initialMargin,
+                true,  // is_test mode
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
+            )
+            .accountsPartial({
+                seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([freshSeller])
+            .rpc();
+
+        await program.methods
+            .purchaseOption()
+            .accountsPartial({
+                option: optionPda,
+                buyer: freshBuyer.publicKey,
+                seller: freshSeller.publicKey,
+            })
+            .signers([freshBuyer, freshSeller])
+            .rpc();
+
+        const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+
+        // Ratio 3 SOL vs strike 2 SOL -> 1 SOL intrinsic value, seller only has 0.5 SOL margin
+        const assetPrice = new anchor.BN(300_000_000);
+        const solPrice = new anchor.BN(100_000_000);
+        const intrinsic = 1_000_000_000;
+
+        await publishPrices(assetPrice, solPrice);
+        await program.methods
+            .exerciseOption()
+            .accountsPartial({
+                option: optionPda,
+                owner: freshBuyer.publicKey,
+                seller: freshSeller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([freshBuyer])
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+
+        assert.equal(optionAccount.status.expired !== undefined, true);
+        assert.equal(optionAccount.settlementShortfall.toNumber(), intrinsic - initialMargin.toNumber());
+        
+        // Entire seller margin went to the owner
+        assert.equal(sellerBalanceAfter, sellerBalanceBefore);
+    });
+
+    describe('Exercise Styles', () => {
+        const lowStrike = new anchor.BN(2_000_000_000); // Strike: 2 SOL per asset unit
+
+        // Creates and purchases a production-mode Call with the given exercise style
+        const createOwnedOption = async (underlyingName: string, exerciseStyle: any) => {
+            const currentTime = Math.floor(Date.now() / 1000);
+            const freshSeller = web3.Keypair.generate();
+            const freshBuyer = web3.Keypair.generate();
+            
+            await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+            await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+            await new Promise(resolve => setTimeout(resolve, 1000));
+            
+            const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
+
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    underlyingName,
+                    new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
+                    optionPrice,
+                    lowStrike,
+                    initialMargin,
+                    false,  // production mode - enforces exercise style
+                    false,  // allow_zero_margin
+                    exerciseStyle,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([freshSeller])
+                .rpc();
+
+            await program.methods
+                .purchaseOption()
+                .accountsPartial({
+                    option: optionPda,
+                    buyer: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                })
+                .signers([freshBuyer, freshSeller])
+                .rpc();
+
+            return { optionPda, freshSeller, freshBuyer };
+        };
+
+        it('Exercises an American option early, netting variation already paid', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createOwnedOption("ASSET/USDC", { american: {} });
+
+            // Ratio 2.05 SOL: daily settlement moves 0.05 SOL of variation to the buyer
+            await publishPrices(new anchor.BN(205_000_000), new anchor.BN(100_000_000));
+            await program.methods
+                .dailySettlement()
+                .accountsPartial({
+                    option: optionPda,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .rpc();
+
+            const optionSettled = await program.account.optionContract.fetch(optionPda);
+            assert.equal(optionSettled.accumulatedVariation.toNumber(), 50_000_000);
+
+            const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+
+            // Ratio 2.1 SOL: 0.1 SOL intrinsic, of which only 0.05 SOL is still owed
+            await publishPrices(new anchor.BN(210_000_000), new anchor.BN(100_000_000));
+            await program.methods
+                .exerciseOption()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([freshBuyer])
+                .rpc();
+
+            const optionAccount = await program.account.optionContract.fetch(optionPda);
+            const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+
+            assert.equal(optionAccount.status.expired !== undefined, true);
+            // Seller paid the full intrinsic value across settlement and exercise
+            assert.equal(sellerBalanceAfter - sellerBalanceBefore, initialMargin.toNumber() - 100_000_000);
+        });
+
+        it('Rejects exercising a Bermudan option outside its exercise dates', async () => {
+            const currentTime = Math.floor(Date.now() / 1000);
+            const exerciseDates = [
+                new anchor.BN(currentTime + 10 * 24 * 60 * 60),
+                new anchor.BN(currentTime + 20 * 24 * 60 * 60),
+            ];
+            const { optionPda, freshSeller, freshBuyer } = await createOwnedOption(
+                "ASSET/USDC",
+                { bermudan: { exerciseDates } }
+            );
+
+            await publishPrices(new anchor.BN(210_000_000), new anchor.BN(100_000_000));
+            try {
+                await program.methods
+                    .exerciseOption()
+                    .accountsPartial({
+                        option: optionPda,
+                        owner: freshBuyer.publicKey,
+                        seller: freshSeller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
+                    })
+                    .signers([freshBuyer])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for exercise outside exercise dates");
+            } catch (error: any) {
+                assert.include(error.toString(), "NotAnExerciseDate");
+            }
+        });
+
+        it('Rejects Bermudan exercise dates that are not ascending', async () => {
+            const currentTime = Math.floor(Date.now() / 1000);
+            const exerciseDates = [
+                new anchor.BN(currentTime + 20 * 24 * 60 * 60),
+                new anchor.BN(currentTime + 10 * 24 * 60 * 60),
+            ];
+            const [, seriesId] = await findNextOptionPda(program, seller.publicKey);
+
+            try {
+                await program.methods
+                    .initializeOption(
+                        seriesId,
+                        CALL_OPTION,
+                        "ASSET/USDC",
+                        new anchor.BN(currentTime),
+                        new anchor.BN(currentTime + THIRTY_DAYS),
+                        optionPrice,
+                        lowStrike,
+                        initialMargin,
+                        true,  // is_test mode
+                        false,  // allow_zero_margin
+                        { bermudan: { exerciseDates } },  // exercise_style
+                        ONE_UNIT,  // contract_size
+                        ONE_UNIT   // quantity
+                    )
+                    .accountsPartial({
+                        seller: seller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
+                    })
+                    .signers([seller])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for unordered exercise dates");
+            } catch (error: any) {
+                assert.include(error.toString(), "InvalidExerciseDates");
+            }
+        });
+    });
+
+    describe('Oracle Price Feeds', () => {
+        let optionPda: web3.PublicKey;
+        let seriesId: anchor.BN;
+        let settler: web3.Keypair;
+
+        before(async () => {
+            const currentTime = Math.floor(Date.now() / 1000);
+            const underlying_oracle = "ASSET/USDC";
+            settler = web3.Keypair.generate();
+            await connection.requestAirdrop(settler.publicKey, 1 * web3.LAMPORTS_PER_SOL);
+            await new Promise(resolve => setTimeout(resolve, 1000));
+
+            [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
+
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    underlying_oracle,
+                    new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
+                    optionPrice,
+                    strikePrice,
+                    initialMargin,
+                    true,  // is_test mode
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: seller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([seller])
+                .rpc();
+
+            await program.methods
+                .purchaseOption()
+                .accountsPartial({
+                    option: optionPda,
+                    buyer: buyer.publicKey,
+                    seller: seller.publicKey,
+                })
+                .signers([buyer, seller])
+                .rpc();
+        });
+
+        it('Rejects price updates from a non-publisher', async () => {
+            const blockTime = await connection.getBlockTime(await connection.getSlot());
+            
+            try {
+                await program.methods
+                    .updatePriceFeed(new anchor.BN(1_000_000), new anchor.BN(0), new anchor.BN(blockTime))
+                    .accountsPartial({
+                        priceFeed: solFeed,
+                        authority: buyer.publicKey,
+                    })
+                    .signers([buyer])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for unauthorized publisher");
+            } catch (error: any) {
+                assert.include(error.toString(), "Unauthorized");
+            }
+        });
+
+        it('Quotes a Black-Scholes premium from oracle prices', async () => {
+            // $7500 / $50 = 150 SOL, at the money against the 150 SOL strike
+            await publishPrices(new anchor.BN(7_500_000_000), new anchor.BN(50_000_000));
+            
+            const premium = await program.methods
+                .quotePremium(new anchor.BN(500_000_000), new anchor.BN(0)) // 50% volatility, 0% rate
+                .accountsPartial({
+                    option: optionPda,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .view();
+
+            // ATM approximation: 0.4 * S * sigma * sqrt(T) ~ 8.6 SOL for 30 days
+            assert.isAbove(premium.toNumber(), 8 * web3.LAMPORTS_PER_SOL);
+            assert.isBelow(premium.toNumber(), 9.2 * web3.LAMPORTS_PER_SOL);
+        });
+
+        it('Rejects settlement against feeds not bound to the option', async () => {
+            await publishPrices(new anchor.BN(100_000_000), new anchor.BN(50_000_000));
+            
+            try {
+                await program.methods
+                    .dailySettlement()
+                    .accountsPartial({
+                        option: optionPda,
+                        settler: settler.publicKey,
+                        assetPriceFeed: solFeed,
+                        solPriceFeed: assetFeed,
+                    })
+                    .signers([settler])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for swapped price feeds");
+            } catch (error: any) {
+                assert.include(error.toString(), "PriceFeedMismatch");
+            }
+        });
+
+        it('Rejects settlement with a wide confidence interval', async () => {
+            // 5% confidence exceeds the 2% limit
+            await publishPrice(program, publisher, assetFeed, new anchor.BN(100_000_000), new anchor.BN(5_000_000));
+            await publishPrice(program, publisher, solFeed, new anchor.BN(50_000_000));
+            
+            try {
+                await program.methods
+                    .dailySettlement()
+                    .accountsPartial({
+                        option: optionPda,
+                        settler: settler.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
+                    })
+                    .signers([settler])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for wide confidence interval");
+            } catch (error: any) {
+                assert.include(error.toString(), "PriceConfidenceTooWide");
+            }
+        });
+
+        it('Rejects options on feeds from a publisher other than the oracle authority', async () => {
+            const rogue = web3.Keypair.generate();
+            await connection.requestAirdrop(rogue.publicKey, 2 * web3.LAMPORTS_PER_SOL);
+            await new Promise(resolve => setTimeout(resolve, 1000));
+            
+            const rogueAssetFeed = await createPriceFeed(program, rogue, "ASSET");
+            const currentTime = Math.floor(Date.now() / 1000);
+            const [, nextSeriesId] = await findNextOptionPda(program, seller.publicKey);
+
+            try {
+                await program.methods
+                    .initializeOption(
+                        nextSeriesId,
+                        CALL_OPTION,
+                        "ASSET/USDC",
+                        new anchor.BN(currentTime),
+                        new anchor.BN(currentTime + THIRTY_DAYS),
+                        optionPrice,
+                        strikePrice,
+                        initialMargin,
+                        true,  // is_test mode
+                        false,  // allow_zero_margin
+                        EUROPEAN,  // exercise_style
+                        ONE_UNIT,  // contract_size
+                        ONE_UNIT   // quantity
+                    )
+                    .accountsPartial({
+                        seller: seller.publicKey,
+                        assetPriceFeed: rogueAssetFeed,
+                        solPriceFeed: solFeed,
+                    })
+                    .signers([seller])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for an untrusted price feed");
+            } catch (error: any) {
+                assert.include(error.toString(), "UntrustedPriceFeed");
+            }
+        });
+
+        it('Rejects an asset feed that does not price the underlying', async () => {
+            const currentTime = Math.floor(Date.now() / 1000);
+            const [, nextSeriesId] = await findNextOptionPda(program, seller.publicKey);
+
+            try {
+                await program.methods
+                    .initializeOption(
+                        nextSeriesId,
+                        CALL_OPTION,
+                        "AAPL/USDC",
+                        new anchor.BN(currentTime),
+                        new anchor.BN(currentTime + THIRTY_DAYS),
+                        optionPrice,
+                        strikePrice,
+                        initialMargin,
+                        true,  // is_test mode
+                        false,  // allow_zero_margin
+                        EUROPEAN,  // exercise_style
+                        ONE_UNIT,  // contract_size
+                        ONE_UNIT   // quantity
+                    )
+                    .accountsPartial({
+                        seller: seller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
+                    })
+                    .signers([seller])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for an asset feed with the wrong symbol");
+            } catch (error: any) {
+                assert.include(error.toString(), "PriceFeedMismatch");
+            }
+        });
+
+        it('Rejects a collateral feed that does not price SOL', async () => {
+            const currentTime = Math.floor(Date.now() / 1000);
+            const [, nextSeriesId] = await findNextOptionPda(program, seller.publicKey);
+
+            try {
+                await program.methods
+                    .initializeOption(
+                        nextSeriesId,
+                        CALL_OPTION,
+                        "ASSET/USDC",
+                        new anchor.BN(currentTime),
+                        new anchor.BN(currentTime + THIRTY_DAYS),
+                        optionPrice,
+                        strikePrice,
+                        initialMargin,
+                        true,  // is_test mode
+                        false,  // allow_zero_margin
+                        EUROPEAN,  // exercise_style
+                        ONE_UNIT,  // contract_size
+                        ONE_UNIT   // quantity
+                    )
+                    .accountsPartial({
+                        seller: seller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: assetFeed,
+                    })
+                    .signers([seller])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for an ASSET feed as the SOL feed");
+            } catch (error: any) {
+                assert.include(error.toString(), "PriceFeedMismatch");
+            }
+        });
+
+        it('Rejects settlement with a stale price', async () => {
+            // Fresh feeds whose only price is older than the maximum age
+            const stalePublisher = web3.Keypair.generate();
+            await connection.requestAirdrop(stalePublisher.publicKey, 2 * web3.LAMPORTS_PER_SOL);
+            await new Promise(resolve => setTimeout(resolve, 1000));
+            
+            const staleAssetFeed = await createPriceFeed(program, stalePublisher, "ASSET");
+            const staleSolFeed = await createPriceFeed(program, stalePublisher, "SOL");
+            await configureOracleAuthority(program, stalePublisher.publicKey);
+            const blockTime = await connection.getBlockTime(await connection.getSlot());
+            const staleTime = new anchor.BN(blockTime - 120);
+            
+            for (const [feed, price] of [[staleAssetFeed, 100_000_000], [staleSolFeed, 50_000_000]] as [web3.PublicKey, number][]) {
+                await program.methods
+                    .updatePriceFeed(new anchor.BN(price), new anchor.BN(0), staleTime)
+                    .accountsPartial({
+                        priceFeed: feed,
+                        authority: stalePublisher.publicKey,
+                    })
+                    .signers([stalePublisher])
+                    .rpc();
+            }
+            
+            const currentTime = Math.floor(Date.now() / 1000);
+            const underlying_stale = "ASSET/USDC";
+            const [stalePda, seriesId] = await findNextOptionPda(program, seller.publicKey);
+
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    underlying_stale,
+                    new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
+                    optionPrice,
+                    strikePrice,
+                    initialMargin,
+                    true,  // is_test mode
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: seller.publicKey,
+                    assetPriceFeed: staleAssetFeed,
+                    solPriceFeed: staleSolFeed,
+                })
+                .signers([seller])
+                .rpc();
+            await configureOracleAuthority(program, publisher.publicKey);
+
+            await program.methods
+                .purchaseOption()
+                .accountsPartial({
+                    option: stalePda,
+                    buyer: buyer.publicKey,
+                    seller: seller.publicKey,
+                })
+                .signers([buyer, seller])
+                .rpc();
+            
+            try {
+                await program.methods
+                    .dailySettlement()
+                    .accountsPartial({
+                        option: stalePda,
+                        settler: settler.publicKey,
+                        assetPriceFeed: staleAssetFeed,
+                        solPriceFeed: staleSolFeed,
+                    })
+                    .signers([settler])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for stale price");
+            } catch (error: any) {
+                assert.include(error.toString(), "StalePrice");
+            }
+        });
+    });
+
+    describe('Expiry Crank', () => {
+        const lowStrike = new anchor.BN(2_000_000_000); // Strike: 2 SOL per asset unit
+
+        // Creates a test-mode Call that expired ten days ago, optionally purchased
+        const createExpiredOption = async (underlyingName: string, purchase: boolean) => {
+            const initiation = Math.floor(Date.now() / 1000) - 40 * 24 * 60 * 60;
+            const freshSeller = web3.Keypair.generate();
+            const freshBuyer = web3.Keypair.generate();
+            
+            await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+            await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+            await new Promise(resolve => setTimeout(resolve, 1000));
+            
+            const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
+
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    underlyingName,
+                    new anchor.BN(initiation),
+                    new anchor.BN(initiation + THIRTY_DAYS),
+                    optionPrice,
+                    lowStrike,
+                    initialMargin,
+                    true,  // is_test mode
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([freshSeller])
+                .rpc();
+
+            if (purchase) {
+                await program.methods
+                    .purchaseOption()
+                    .accountsPartial({
+                        option: optionPda,
+                        buyer: freshBuyer.publicKey,
+                        seller: freshSeller.publicKey,
+                    })
+                    .signers([freshBuyer, freshSeller])
+                    .rpc();
+            }
+
+            return { optionPda, freshSeller, freshBuyer };
+        };
+
+        it('Expires a listed option past its expiry date', async () => {
+            const { optionPda, freshSeller } = await createExpiredOption("ASSET/USDC", false);
+
+            await program.methods
+                .expireOption()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: null,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .rpc();
+
+            const optionAccount = await program.account.optionContract.fetch(optionPda);
+            assert.equal(optionAccount.status.expired !== undefined, true);
+        });
+
+        it('Returns both margins when an owned option expires out of the money', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createExpiredOption("ASSET/USDC", true);
+            
+            const buyerBalanceBefore = await connection.getBalance(freshBuyer.publicKey);
+            const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+
+            // Ratio 1.5 SOL is below the 2 SOL strike
+            await publishPrices(new anchor.BN(150_000_000), new anchor.BN(100_000_000));
+            await program.methods
+                .expireOption()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .rpc();
+
+            const optionAccount = await program.account.optionContract.fetch(optionPda);
+            const buyerBalanceAfter = await connection.getBalance(freshBuyer.publicKey);
+            const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+            
+            assert.equal(optionAccount.status.expired !== undefined, true);
+            assert.equal(optionAccount.buyerMargin.toNumber(), 0);
+            assert.equal(optionAccount.sellerMargin.toNumber(), 0);
+            assert.equal(buyerBalanceAfter - buyerBalanceBefore, initialMargin.toNumber());
+            assert.equal(sellerBalanceAfter - sellerBalanceBefore, initialMargin.toNumber());
+        });
+
+        it('Records the expiry price and leaves an in-the-money option for exercise', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createExpiredOption("ASSET/USDC", true);
+
+            // Ratio 2.1 SOL is above the 2 SOL strike
+            await publishPrices(new anchor.BN(210_000_000), new anchor.BN(100_000_000));
+            await program.methods
+                .expireOption()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .rpc();
+
+            const optionAccount = await program.account.optionContract.fetch(optionPda);
+            assert.equal(optionAccount.status.owned !== undefined, true);
+            assert.equal(optionAccount.expiryPrice.toNumber(), 2_100_000_000);
+            assert.equal(optionAccount.buyerMargin.toNumber(), initialMargin.toNumber());
+
+            // Exercise settles at the recorded price even after the market moves
+            await publishPrices(new anchor.BN(300_000_000), new anchor.BN(100_000_000));
+            const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+            
+            await program.methods
+                .exerciseOption()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([freshBuyer])
+                .rpc();
+
+            const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+            assert.equal(sellerBalanceAfter - sellerBalanceBefore, initialMargin.toNumber() - 100_000_000);
+        });
+
+        it('Settles an unexercised in-the-money option once the grace period has passed', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createExpiredOption("ASSET/USDC", true);
+
+            // Ratio 2.1 SOL vs strike 2 SOL -> 0.1 SOL intrinsic value recorded at expiry
+            await publishPrices(new anchor.BN(210_000_000), new anchor.BN(100_000_000));
+            const expire = () => program.methods
+                .expireOption()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .rpc();
+            await expire();
+
+            const buyerBalanceBefore = await connection.getBalance(freshBuyer.publicKey);
+            const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+
+            // The option expired ten days ago, so anyone can now settle it at the recorded price
+            await publishPrices(new anchor.BN(150_000_000), new anchor.BN(100_000_000));
+            await expire();
+
+            const optionAccount = await program.account.optionContract.fetch(optionPda);
+            const buyerBalanceAfter = await connection.getBalance(freshBuyer.publicKey);
+            const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+
+            assert.equal(optionAccount.status.expired !== undefined, true);
+            assert.equal(optionAccount.buyerMargin.toNumber(), 0);
+            assert.equal(optionAccount.sellerMargin.toNumber(), 0);
+            assert.equal(optionAccount.lastSettlementPrice.toNumber(), 2_100_000_000);
+            assert.equal(buyerBalanceAfter - buyerBalanceBefore, initialMargin.toNumber() + 100_000_000);
+            assert.equal(sellerBalanceAfter - sellerBalanceBefore, initialMargin.toNumber() - 100_000_000);
+        });
+
+        it('Rejects expiring a delisted option', async () => {
+            const { optionPda, freshSeller } = await createExpiredOption("ASSET/USDC", false);
+
+            await program.methods
+                .delistOption()
+                .accountsPartial({
+                    option: optionPda,
+                    seller: freshSeller.publicKey,
+                })
+                .signers([freshSeller])
+                .rpc();
+
+            try {
+                await program.methods
+                    .expireOption()
+                    .accountsPartial({
+                        option: optionPda,
+                        owner: null,
+                        seller: freshSeller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
+                    })
+                    .rpc();
+                
+                assert.fail("Should have thrown error for expiring a delisted option");
+            } catch (error: any) {
+                assert.include(error.toString(), "CannotExpireOption");
+            }
+        });
+
+        it('Auto-exercises an in-the-money option and pays the keeper bounty', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createExpiredOption("ASSET/USDC", true);
+            const keeper = web3.Keypair.generate();
+            await connection.requestAirdrop(keeper.publicKey, web3.LAMPORTS_PER_SOL);
+            await new Promise(resolve => setTimeout(resolve, 1000));
+
+            const optionBefore = await program.account.optionContract.fetch(optionPda);
+            const keeperBounty = optionBefore.keeperBounty.toNumber();
+            const buyerBalanceBefore = await connection.getBalance(freshBuyer.publicKey);
+            const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+            const keeperBalanceBefore = await connection.getBalance(keeper.publicKey);
+
+            // Ratio 2.1 SOL is above the 2 SOL strike: 0.1 SOL intrinsic value
+            await publishPrices(new anchor.BN(210_000_000), new anchor.BN(100_000_000));
+            await program.methods
+                .autoExercise()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                    keeper: keeper.publicKey,
+                })
+                .signers([keeper])
+                .rpc();
+
+            const optionAccount = await program.account.optionContract.fetch(optionPda);
+            const buyerBalanceAfter = await connection.getBalance(freshBuyer.publicKey);
+            const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+            const keeperBalanceAfter = await connection.getBalance(keeper.publicKey);
+
+            assert.equal(optionAccount.status.expired !== undefined, true);
+            assert.equal(optionAccount.buyerMargin.toNumber(), 0);
+            assert.equal(optionAccount.sellerMargin.toNumber(), 0);
+            assert.equal(buyerBalanceAfter - buyerBalanceBefore, initialMargin.toNumber() + 100_000_000 - keeperBounty);
+            assert.equal(sellerBalanceAfter - sellerBalanceBefore, initialMargin.toNumber() - 100_000_000);
+            // Keeper pays the transaction fee out of the bounty
+            assert.isAbove(keeperBalanceAfter - keeperBalanceBefore, 0);
+        });
+
+        it('Rejects auto-exercise when intrinsic value does not exceed the keeper bounty', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createExpiredOption("ASSET/USDC", true);
+
+            // Owner raises the bounty above the 0.1 SOL intrinsic value
+            await program.methods
+                .setKeeperBounty(new anchor.BN(200_000_000))
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                })
+                .signers([freshBuyer])
+                .rpc();
+
+            await publishPrices(new anchor.BN(210_000_000), new anchor.BN(100_000_000));
+            try {
+                await program.methods
+                    .autoExercise()
+                    .accountsPartial({
+                        option: optionPda,
+                        owner: freshBuyer.publicKey,
+                        seller: freshSeller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
+                        keeper: provider.wallet.publicKey,
+                    })
+                    .rpc();
+                
+                assert.fail("Should have thrown error for auto-exercise below the bounty");
+            } catch (error: any) {
+                assert.include(error.toString(), "BelowAutoExerciseThreshold");
+            }
+        });
+
+        it('Settles an option worth less than the keeper bounty through the expiry crank', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createExpiredOption("ASSET/USDC", true);
+
+            // Keeper bounty above the 0.1 SOL intrinsic value rules out auto-exercise
+            await program.methods
+                .setKeeperBounty(new anchor.BN(200_000_000))
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                })
+                .signers([freshBuyer])
+                .rpc();
+
+            await publishPrices(new anchor.BN(210_000_000), new anchor.BN(100_000_000));
+            const expire = () => program.methods
+                .expireOption()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .rpc();
+
+            // First crank records the expiry price, the second settles past the grace period
+            await expire();
+            const buyerBalanceBefore = await connection.getBalance(freshBuyer.publicKey);
+            await expire();
+
+            const optionAccount = await program.account.optionContract.fetch(optionPda);
+            const buyerBalanceAfter = await connection.getBalance(freshBuyer.publicKey);
+
+            assert.equal(optionAccount.status.expired !== undefined, true);
+            assert.equal(optionAccount.buyerMargin.toNumber(), 0);
+            assert.equal(optionAccount.sellerMargin.toNumber(), 0);
+            // Owner receives the full intrinsic value with no bounty deducted
+            assert.equal(buyerBalanceAfter - buyerBalanceBefore, initialMargin.toNumber() + 100_000_000);
+        });
+    });
+
+    describe('Margin Call Resolution', () => {
+        const lowStrike = new anchor.BN(2_000_000_000); // Strike: 2 SOL per asset unit
+
+        // Creates a purchased Call and settles it at ratio 3 so the seller is margin called
+        const createMarginCalledOption = async (underlyingName: string) => {
+            const currentTime = Math.floor(Date.now() / 1000);
+            const freshSeller = web3.Keypair.generate();
+            const freshBuyer = web3.Keypair.generate();
+            const settler = web3.Keypair.generate();
+            
+            await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+            await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+            await connection.requestAirdrop(settler.publicKey, 1 * web3.LAMPORTS_PER_SOL);
+            await new Promise(resolve => setTimeout(resolve, 1000));
+            
+            const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
+
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    underlyingName,
+                    new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
+                    optionPrice,
+                    lowStrike,
+                    initialMargin,
+                    true,  // is_test mode
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([freshSeller])
+                .rpc();
+
+            await program.methods
+                .purchaseOption()
+                .accountsPartial({
+                    option: optionPda,
+                    buyer: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                })
+                .signers([freshBuyer, freshSeller])
+                .rpc();
+
+            await publishPrices(new anchor.BN(300_000_000), new anchor.BN(100_000_000));
+            await program.methods
+                .dailySettlement()
+                .accountsPartial({
+                    option: optionPda,
+                    settler: settler.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([settler])
+                .rpc();
+
+            return { optionPda, freshSeller, freshBuyer };
+        };
+
+        it('Cures a margin call when the seller tops up to the initial margin', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createMarginCalledOption("ASSET/USDC");
+            
+            const optionBefore = await program.account.optionContract.fetch(optionPda);
+            assert.equal(optionBefore.status.marginCalled !== undefined, true);
+            
+            // Buyer is not the under-margined party and cannot top up
+            try {
+                await program.methods
+                    .topUpMargin(initialMargin)
+                    .accountsPartial({
+                        option: optionPda,
+                        depositor: freshBuyer.publicKey,
+                    })
+                    .signers([freshBuyer])
+                    .rpc();
+                
+                assert.fail("Should have thrown error for top up by the wrong party");
+            } catch (error: any) {
+                assert.include(error.toString(), "Unauthorized");
+            }
+            
+            const topUp = initialMargin.sub(optionBefore.sellerMargin);
+            await program.methods
+                .topUpMargin(topUp)
+                .accountsPartial({
+                    option: optionPda,
+                    depositor: freshSeller.publicKey,
+                })
+                .signers([freshSeller])
+                .rpc();
+
+            const optionAfter = await program.account.optionContract.fetch(optionPda);
+            assert.equal(optionAfter.status.owned !== undefined, true);
+            assert.equal(optionAfter.sellerMargin.toNumber(), initialMargin.toNumber());
+            assert.equal(optionAfter.marginCallDate.toNumber(), 0);
+        });
+
+        it('Closes a margin-called option and returns both margins', async () => {
+            const { optionPda, freshSeller, freshBuyer } = await createMarginCalledOption("ASSET/USDC");
+            
+            const optionBefore = await program.account.optionContract.fetch(optionPda);
+            const rent = await connection.getBalance(optionPda)
+                - optionBefore.buyerMargin.toNumber()
+                - optionBefore.sellerMargin.toNumber();
+            const buyerBalanceBefore = await connection.getBalance(freshBuyer.publicKey);
+            const sellerBalanceBefore = await connection.getBalance(freshSeller.publicKey);
+
+            await program.methods
+                .closeMarginCalled()
+                .accountsPartial({
+                    option: optionPda,
+                    owner: freshBuyer.publicKey,
+                    seller: freshSeller.publicKey,
+                })
+                .rpc();
+
+            const buyerBalanceAfter = await connection.getBalance(freshBuyer.publicKey);
+            const sellerBalanceAfter = await connection.getBalance(freshSeller.publicKey);
+            
+            assert.equal(buyerBalanceAfter - buyerBalanceBefore, optionBefore.buyerMargin.toNumber());
+            assert.equal(sellerBalanceAfter - sellerBalanceBefore, optionBefore.sellerMargin.toNumber() + rent);
+            
+            // Option account is closed
+            const closed = await connection.getAccountInfo(optionPda);
+            assert.isNull(closed);
+        });
+    });
+
+    // Zero Margin Tests
+    describe('Zero Margin Options (Test Mode)', () => {
+        it('Allows zero margin in test mode with allow_zero_margin=true', async () => {
+            const currentTime = Math.floor(Date.now() / 1000);
+            const underlying13 = "ASSET/USDC";
+            const zeroMargin = new anchor.BN(0); // Zero margin
+            
+            const freshSeller = web3.Keypair.generate();
+            await connection.requestAirdrop(freshSeller.publicKey, 5 * web3.LAMPORTS_PER_SOL);
+            await new Promise(resolve => setTimeout(resolve, 1000));
+            
+            const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
+
+            // Initialize with zero margin and allow_zero_margin=true (should succeed)
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    underlying13,
+                    new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
+                    optionPrice,
+                    strikePrice,
+                    zeroMargin,
+                    true,  // is_test mode
+                    true,   // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([freshSeller])
+                .rpc();
+
+            const optionAccount = await program.account.optionContract.fetch(optionPda);
+            assert.equal(optionAccount.initialMargin.toNumber(), 0);
+            assert.equal(optionAccount.allowZeroMargin, true);
+            assert.equal(optionAccount.isTest, true);
+        });
 
         it('Rejects zero margin in test mode with allow_zero_margin=false', async () => {
             const currentTime = Math.floor(Date.now() / 1000);
-            const underlying14 = "ZERO2/USDC";
+            const underlying14 = "ASSET/USDC";
             const zeroMargin = new anchor.BN(0);
             
             const freshSeller = web3.Keypair.generate();
@@ -1139,20 +2854,28 @@ describe('Options Contract', () => {
             await new Promise(resolve => setTimeout(resolve, 1000));
 
             // Try to initialize with zero margin and allow_zero_margin=false (should fail)
+            const [, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
             try {
                 await program.methods
                     .initializeOption(
+                        seriesId,
                         CALL_OPTION,
                         underlying14,
                         new anchor.BN(currentTime),
+                        new anchor.BN(currentTime + THIRTY_DAYS),
                         optionPrice,
                         strikePrice,
                         zeroMargin,
                         true,  // is_test mode
-                        false  // allow_zero_margin = false
+                        false,  // allow_zero_margin = false
+                        EUROPEAN,  // exercise_style
+                        ONE_UNIT,  // contract_size
+                        ONE_UNIT   // quantity
                     )
                     .accountsPartial({
                         seller: freshSeller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
                     })
                     .signers([freshSeller])
                     .rpc();
@@ -1165,7 +2888,7 @@ describe('Options Contract', () => {
 
         it('Rejects zero margin in production mode even with allow_zero_margin=true', async () => {
             const currentTime = Math.floor(Date.now() / 1000);
-            const underlying15 = "ZERO3/USDC";
+            const underlying15 = "ASSET/USDC";
             const zeroMargin = new anchor.BN(0);
             
             const freshSeller = web3.Keypair.generate();
@@ -1173,20 +2896,28 @@ describe('Options Contract', () => {
             await new Promise(resolve => setTimeout(resolve, 1000));
 
             // Try to initialize with zero margin in production mode (should fail)
+            const [, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
             try {
                 await program.methods
                     .initializeOption(
+                        seriesId,
                         CALL_OPTION,
                         underlying15,
                         new anchor.BN(currentTime),
+                        new anchor.BN(currentTime + THIRTY_DAYS),
                         optionPrice,
                         strikePrice,
                         zeroMargin,
                         false,  // production mode
-                        true    // allow_zero_margin (shouldn't matter in production)
+                        true,    // allow_zero_margin (shouldn't matter in production)
+                        EUROPEAN,  // exercise_style
+                        ONE_UNIT,  // contract_size
+                        ONE_UNIT   // quantity
                     )
                     .accountsPartial({
                         seller: freshSeller.publicKey,
+                        assetPriceFeed: assetFeed,
+                        solPriceFeed: solFeed,
                     })
                     .signers([freshSeller])
                     .rpc();
@@ -1199,7 +2930,7 @@ describe('Options Contract', () => {
 
         it('Purchases zero-margin option without requiring margin deposits', async () => {
             const currentTime = Math.floor(Date.now() / 1000);
-            const underlying16 = "ZERO4/USDC";
+            const underlying16 = "ASSET/USDC";
             const zeroMargin = new anchor.BN(0);
             
             const freshSeller = web3.Keypair.generate();
@@ -1209,25 +2940,29 @@ describe('Options Contract', () => {
             await connection.requestAirdrop(freshBuyer.publicKey, 5 * web3.LAMPORTS_PER_SOL);
             await new Promise(resolve => setTimeout(resolve, 1000));
             
-            const [optionPda] = web3.PublicKey.findProgramAddressSync(
-                [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying16)],
-                program.programId
-            );
+            const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
             // Initialize zero-margin option
             await program.methods
                 .initializeOption(
+                    seriesId,
                     PUT_OPTION,
                     underlying16,
                     new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
                     optionPrice,
                     strikePrice,
                     zeroMargin,
                     true,  // is_test mode
-                    true   // allow_zero_margin
+                    true,   // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
                 )
                 .accountsPartial({
                     seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([freshSeller])
                 .rpc();
@@ -1267,7 +3002,7 @@ describe('Options Contract', () => {
 
         it('Allows reselling zero-margin option without margin transfers', async () => {
             const currentTime = Math.floor(Date.now() / 1000);
-            const underlying17 = "ZERO5/USDC";
+            const underlying17 = "ASSET/USDC";
             const zeroMargin = new anchor.BN(0);
             
             const freshSeller = web3.Keypair.generate();
@@ -1279,25 +3014,29 @@ describe('Options Contract', () => {
             await connection.requestAirdrop(freshBuyer2.publicKey, 5 * web3.LAMPORTS_PER_SOL);
             await new Promise(resolve => setTimeout(resolve, 1000));
             
-            const [optionPda] = web3.PublicKey.findProgramAddressSync(
-                [Buffer.from("option"), freshSeller.publicKey.toBuffer(), Buffer.from(underlying17)],
-                program.programId
-            );
+            const [optionPda, seriesId] = await findNextOptionPda(program, freshSeller.publicKey);
 
             // Initialize and purchase zero-margin option
             await program.methods
                 .initializeOption(
+                    seriesId,
                     CALL_OPTION,
                     underlying17,
                     new anchor.BN(currentTime),
+                    new anchor.BN(currentTime + THIRTY_DAYS),
                     optionPrice,
                     strikePrice,
                     zeroMargin,
                     true,  // is_test mode
-                    true   // allow_zero_margin
+                    true,   // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
                 )
                 .accountsPartial({
                     seller: freshSeller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
                 })
                 .signers([freshSeller])
                 .rpc();
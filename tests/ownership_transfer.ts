@@ -2,6 +2,8 @@ import * as anchor from '@coral-xyz/anchor';
 import { AnchorProvider, Program, web3 } from '@coral-xyz/anchor';
 import { assert } from 'chai';
 import { Escrow } from '../target/types/escrow';
+import { configureOracleAuthority, createPriceFeed, publishPrice } from './utils/price_feed';
+import { findNextOptionPda } from './utils/option_pda';
 
 /**
  * Multiple Ownership Transfer Test Suite
@@ -23,9 +25,23 @@ describe('Multiple Ownership Transfer Test (5 Transfers)', () => {
     let seller: web3.Keypair;
     let buyers: web3.Keypair[] = [];
     let optionPda: web3.PublicKey;
+    let seriesId: anchor.BN;
     
     const underlying = "AAPL/SOL";
     const CALL_OPTION = 0;
+    const EUROPEAN = { european: {} };
+    const ONE_UNIT = new anchor.BN(1); // Contract size and quantity of a single-unit contract
+    const THIRTY_DAYS = 30 * 24 * 60 * 60; // Standard tenor in seconds
+
+    // Oracle price feeds published by a local test authority
+    let publisher: web3.Keypair;
+    let assetFeed: web3.PublicKey;
+    let solFeed: web3.PublicKey;
+
+    const publishPrices = async (assetPrice: anchor.BN, solPrice: anchor.BN) => {
+        await publishPrice(program, publisher, assetFeed, assetPrice);
+        await publishPrice(program, publisher, solFeed, solPrice);
+    };
     
     // Contract parameters
     const optionPrice = new anchor.BN(2 * web3.LAMPORTS_PER_SOL); // 2 SOL premium
@@ -56,6 +72,7 @@ describe('Multiple Ownership Transfer Test (5 Transfers)', () => {
     before(async () => {
         // Generate seller and 5 buyers
         seller = web3.Keypair.generate();
+        publisher = web3.Keypair.generate();
         for (let i = 0; i < 5; i++) {
             buyers.push(web3.Keypair.generate());
         }
@@ -66,14 +83,16 @@ describe('Multiple Ownership Transfer Test (5 Transfers)', () => {
         for (const buyer of buyers) {
             await connection.requestAirdrop(buyer.publicKey, 10 * web3.LAMPORTS_PER_SOL);
         }
+        await connection.requestAirdrop(publisher.publicKey, 2 * web3.LAMPORTS_PER_SOL);
         
         await new Promise(resolve => setTimeout(resolve, 2000));
         
+        assetFeed = await createPriceFeed(program, publisher, "AAPL");
+        solFeed = await createPriceFeed(program, publisher, "SOL");
+        await configureOracleAuthority(program, publisher.publicKey);
+        
         // Calculate PDA (this is the contract identifier)
-        [optionPda] = web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("option"), seller.publicKey.toBuffer(), Buffer.from(underlying)],
-            program.programId
-        );
+        [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
         
         console.log('');
         console.log('═══════════════════════════════════════════════════════');
@@ -101,17 +120,24 @@ describe('Multiple Ownership Transfer Test (5 Transfers)', () => {
         
         await program.methods
             .initializeOption(
+                seriesId,
                 CALL_OPTION,
                 underlying,
                 new anchor.BN(initiationDate),
+                new anchor.BN(initiationDate + THIRTY_DAYS),
                 optionPrice,
                 strikePrice,
                 initialMargin,
                 true,  // Test mode
-                false  // allow_zero_margin
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
             )
             .accountsPartial({
                 seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([seller])
             .rpc();
@@ -271,11 +297,15 @@ describe('Multiple Ownership Transfer Test (5 Transfers)', () => {
         console.log(`   Current Ratio: ${currentRatio.toFixed(3)} | Strike: ${strikeRatio}`);
         console.log(`   Status: ${currentRatio < strikeRatio ? 'IN THE MONEY' : 'OUT OF THE MONEY'}`);
         
+        await publishPrices(toPrice(aaplPrice), toPrice(solPrice));
         await program.methods
-            .exerciseOption(toPrice(aaplPrice), toPrice(solPrice))
+            .exerciseOption()
             .accountsPartial({
                 option: optionPda,
                 owner: buyers[4].publicKey,
+                seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([buyers[4]])
             .rpc();
@@ -316,11 +346,14 @@ describe('Multiple Ownership Transfer Test (5 Transfers)', () => {
         
         const optionBefore = await program.account.optionContract.fetch(optionPda);
         
+        await publishPrices(toPrice(aaplPrice), toPrice(solPrice));
         await program.methods
-            .dailySettlement(toPrice(aaplPrice), toPrice(solPrice))
+            .dailySettlement()
             .accountsPartial({
                 option: optionPda,
                 settler: settler.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
             })
             .signers([settler])
             .rpc();
@@ -0,0 +1,549 @@
+import * as anchor from '@coral-xyz/anchor';
+import { AnchorProvider, Program, web3 } from '@coral-xyz/anchor';
+import {
+    TOKEN_PROGRAM_ID,
+    createMint,
+    createAccount,
+    mintTo,
+    getAccount,
+} from '@solana/spl-token';
+import { assert } from 'chai';
+import { Escrow } from '../target/types/escrow';
+import { configureOracleAuthority, createPriceFeed, publishPrice } from './utils/price_feed';
+import { findNextOptionPda } from './utils/option_pda';
+
+/**
+ * Token Collateral Test Suite
+ *
+ * AAPL/USDC Call option where premium, strike and margins are denominated
+ * in a USDC-like mint (6 decimals) and margins are escrowed in a token vault PDA
+ * Strike: 200 USDC | Premium: 5 USDC | Margin: 50 USDC
+ */
+
+describe('Token Collateral Options', () => {
+    const provider = AnchorProvider.local();
+    const program = anchor.workspace.Escrow as Program<Escrow>;
+    const connection = provider.connection;
+
+    const CALL_OPTION = 0;
+    const EUROPEAN = { european: {} };
+    const ONE_UNIT = new anchor.BN(1); // Contract size and quantity of a single-unit contract
+    const THIRTY_DAYS = 30 * 24 * 60 * 60; // Standard tenor in seconds
+    const USDC = 1_000_000; // 6 decimals
+
+    const optionPrice = new anchor.BN(5 * USDC);
+    const strikePrice = new anchor.BN(200 * USDC);
+    const initialMargin = new anchor.BN(50 * USDC);
+
+    let seller: web3.Keypair;
+    let buyer: web3.Keypair;
+    let newBuyer: web3.Keypair;
+    let publisher: web3.Keypair;
+    let assetFeed: web3.PublicKey;
+    let usdcFeed: web3.PublicKey;
+    let solFeed: web3.PublicKey;
+    let usdcMint: web3.PublicKey;
+    let sellerTokenAccount: web3.PublicKey;
+    let buyerTokenAccount: web3.PublicKey;
+    let newBuyerTokenAccount: web3.PublicKey;
+    let optionPda: web3.PublicKey;
+    let vaultPda: web3.PublicKey;
+
+    const publishPrices = async (assetPrice: anchor.BN, usdcPrice: anchor.BN) => {
+        await publishPrice(program, publisher, assetFeed, assetPrice);
+        await publishPrice(program, publisher, usdcFeed, usdcPrice);
+    };
+
+    const tokenBalance = async (account: web3.PublicKey): Promise<number> => {
+        return Number((await getAccount(connection, account)).amount);
+    };
+
+    before(async () => {
+        seller = web3.Keypair.generate();
+        buyer = web3.Keypair.generate();
+        newBuyer = web3.Keypair.generate();
+        publisher = web3.Keypair.generate();
+
+        for (const keypair of [seller, buyer, newBuyer, publisher]) {
+            await connection.requestAirdrop(keypair.publicKey, 2 * web3.LAMPORTS_PER_SOL);
+        }
+        await new Promise(resolve => setTimeout(resolve, 1000));
+
+        assetFeed = await createPriceFeed(program, publisher, "AAPL");
+        usdcFeed = await createPriceFeed(program, publisher, "USDC");
+        solFeed = await createPriceFeed(program, publisher, "SOL");
+        await configureOracleAuthority(program, publisher.publicKey);
+
+        // USDC-like mint controlled by the publisher, funded to each party
+        usdcMint = await createMint(connection, publisher, publisher.publicKey, null, 6);
+        sellerTokenAccount = await createAccount(connection, seller, usdcMint, seller.publicKey);
+        buyerTokenAccount = await createAccount(connection, buyer, usdcMint, buyer.publicKey);
+        newBuyerTokenAccount = await createAccount(connection, newBuyer, usdcMint, newBuyer.publicKey);
+        for (const account of [sellerTokenAccount, buyerTokenAccount, newBuyerTokenAccount]) {
+            await mintTo(connection, publisher, usdcMint, account, publisher, 1_000 * USDC);
+        }
+
+        let seriesId: anchor.BN;
+        [optionPda, seriesId] = await findNextOptionPda(program, seller.publicKey);
+        [vaultPda] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), optionPda.toBuffer()],
+            program.programId
+        );
+
+        const currentTime = Math.floor(Date.now() / 1000);
+        await program.methods
+            .initializeOption(
+                seriesId,
+                CALL_OPTION,
+                "AAPL/USDC",
+                new anchor.BN(currentTime),
+                new anchor.BN(currentTime + THIRTY_DAYS),
+                optionPrice,
+                strikePrice,
+                initialMargin,
+                true,  // is_test mode
+                false,  // allow_zero_margin
+                EUROPEAN,  // exercise_style
+                ONE_UNIT,  // contract_size
+                ONE_UNIT   // quantity
+            )
+            .accountsPartial({
+                seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: solFeed,
+            })
+            .signers([seller])
+            .rpc();
+    });
+
+    it('Rejects a collateral feed that does not price the underlying quote currency', async () => {
+        try {
+            await program.methods
+                .initializeTokenVault()
+                .accountsPartial({
+                    option: optionPda,
+                    collateralMint: usdcMint,
+                    vault: vaultPda,
+                    seller: seller.publicKey,
+                    collateralPriceFeed: assetFeed,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([seller])
+                .rpc();
+
+            assert.fail("Should have thrown error for an AAPL feed pricing USDC collateral");
+        } catch (error: any) {
+            assert.include(error.toString(), "PriceFeedMismatch");
+        }
+    });
+
+    it('Attaches a collateral mint and creates the token vault', async () => {
+        await program.methods
+            .initializeTokenVault()
+            .accountsPartial({
+                option: optionPda,
+                collateralMint: usdcMint,
+                vault: vaultPda,
+                seller: seller.publicKey,
+                collateralPriceFeed: usdcFeed,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([seller])
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        assert.equal(optionAccount.collateralMint.toString(), usdcMint.toString());
+        assert.equal(optionAccount.collateralDecimals, 6);
+        assert.equal(optionAccount.solPriceFeed.toString(), usdcFeed.toString());
+
+        const vault = await getAccount(connection, vaultPda);
+        assert.equal(vault.owner.toString(), optionPda.toString());
+        assert.equal(Number(vault.amount), 0);
+    });
+
+    it('Rejects native SOL purchase of a token option', async () => {
+        try {
+            await program.methods
+                .purchaseOption()
+                .accountsPartial({
+                    option: optionPda,
+                    buyer: buyer.publicKey,
+                    seller: seller.publicKey,
+                })
+                .signers([buyer, seller])
+                .rpc();
+
+            assert.fail("Should have thrown error for native purchase of a token option");
+        } catch (error: any) {
+            assert.include(error.toString(), "TokenCollateralOption");
+        }
+    });
+
+    it('Purchases with token premium and escrows both margins in the vault', async () => {
+        const buyerBefore = await tokenBalance(buyerTokenAccount);
+        const sellerBefore = await tokenBalance(sellerTokenAccount);
+
+        await program.methods
+            .purchaseTokenOption()
+            .accountsPartial({
+                option: optionPda,
+                collateralMint: usdcMint,
+                vault: vaultPda,
+                buyerTokenAccount,
+                sellerTokenAccount,
+                buyer: buyer.publicKey,
+                seller: seller.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([buyer, seller])
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        assert.equal(optionAccount.status.owned !== undefined, true);
+        assert.equal(optionAccount.owner.toString(), buyer.publicKey.toString());
+
+        assert.equal(await tokenBalance(vaultPda), 2 * initialMargin.toNumber());
+        assert.equal(buyerBefore - await tokenBalance(buyerTokenAccount), optionPrice.toNumber() + initialMargin.toNumber());
+        assert.equal(await tokenBalance(sellerTokenAccount) - sellerBefore, optionPrice.toNumber() - initialMargin.toNumber());
+    });
+
+    it('Resells and swaps the buyer margin held in the vault', async () => {
+        const resellPrice = new anchor.BN(8 * USDC);
+        const buyerBefore = await tokenBalance(buyerTokenAccount);
+
+        await program.methods
+            .resellTokenOption(resellPrice)
+            .accountsPartial({
+                option: optionPda,
+                collateralMint: usdcMint,
+                vault: vaultPda,
+                currentOwnerTokenAccount: buyerTokenAccount,
+                newBuyerTokenAccount,
+                currentOwner: buyer.publicKey,
+                newBuyer: newBuyer.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([buyer, newBuyer])
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        assert.equal(optionAccount.owner.toString(), newBuyer.publicKey.toString());
+        assert.equal(await tokenBalance(buyerTokenAccount) - buyerBefore, resellPrice.toNumber() + initialMargin.toNumber());
+        assert.equal(await tokenBalance(vaultPda), 2 * initialMargin.toNumber());
+    });
+
+    it('Exercises in USDC and closes the vault', async () => {
+        const ownerBefore = await tokenBalance(newBuyerTokenAccount);
+        const sellerBefore = await tokenBalance(sellerTokenAccount);
+
+        // AAPL $210 / USDC $1 -> 210 USDC vs 200 USDC strike: 10 USDC intrinsic
+        await publishPrices(new anchor.BN(210_000_000), new anchor.BN(1_000_000));
+        await program.methods
+            .exerciseTokenOption()
+            .accountsPartial({
+                option: optionPda,
+                collateralMint: usdcMint,
+                vault: vaultPda,
+                ownerTokenAccount: newBuyerTokenAccount,
+                sellerTokenAccount,
+                owner: newBuyer.publicKey,
+                seller: seller.publicKey,
+                assetPriceFeed: assetFeed,
+                solPriceFeed: usdcFeed,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([newBuyer])
+            .rpc();
+
+        const optionAccount = await program.account.optionContract.fetch(optionPda);
+        assert.equal(optionAccount.status.expired !== undefined, true);
+        assert.equal(optionAccount.buyerMargin.toNumber(), 0);
+        assert.equal(optionAccount.sellerMargin.toNumber(), 0);
+
+        assert.equal(await tokenBalance(newBuyerTokenAccount) - ownerBefore, initialMargin.toNumber() + 10 * USDC);
+        assert.equal(await tokenBalance(sellerTokenAccount) - sellerBefore, initialMargin.toNumber() - 10 * USDC);
+        assert.isNull(await connection.getAccountInfo(vaultPda));
+    });
+
+    describe('Settlement paths', () => {
+        // Creates a token option with its vault, optionally purchased by the buyer
+        const createTokenOption = async (underlyingName: string, initiation: number, purchase: boolean) => {
+            const [pda, seriesId] = await findNextOptionPda(program, seller.publicKey);
+            const [vault] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("vault"), pda.toBuffer()],
+                program.programId
+            );
+
+            await program.methods
+                .initializeOption(
+                    seriesId,
+                    CALL_OPTION,
+                    underlyingName,
+                    new anchor.BN(initiation),
+                    new anchor.BN(initiation + THIRTY_DAYS),
+                    optionPrice,
+                    strikePrice,
+                    initialMargin,
+                    true,  // is_test mode
+                    false,  // allow_zero_margin
+                    EUROPEAN,  // exercise_style
+                    ONE_UNIT,  // contract_size
+                    ONE_UNIT   // quantity
+                )
+                .accountsPartial({
+                    seller: seller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: solFeed,
+                })
+                .signers([seller])
+                .rpc();
+
+            await program.methods
+                .initializeTokenVault()
+                .accountsPartial({
+                    option: pda,
+                    collateralMint: usdcMint,
+                    vault,
+                    seller: seller.publicKey,
+                    collateralPriceFeed: usdcFeed,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([seller])
+                .rpc();
+
+            if (purchase) {
+                await program.methods
+                    .purchaseTokenOption()
+                    .accountsPartial({
+                        option: pda,
+                        collateralMint: usdcMint,
+                        vault,
+                        buyerTokenAccount,
+                        sellerTokenAccount,
+                        buyer: buyer.publicKey,
+                        seller: seller.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([buyer, seller])
+                    .rpc();
+            }
+
+            return { pda, vault };
+        };
+
+        // Creates a purchased token option and settles it at 300 USDC so the seller is margin called
+        const createMarginCalledTokenOption = async (underlyingName: string) => {
+            const { pda, vault } = await createTokenOption(underlyingName, Math.floor(Date.now() / 1000), true);
+
+            await publishPrices(new anchor.BN(300_000_000), new anchor.BN(1_000_000));
+            await program.methods
+                .dailySettlement()
+                .accountsPartial({
+                    option: pda,
+                    settler: provider.wallet.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: usdcFeed,
+                })
+                .rpc();
+
+            const optionAccount = await program.account.optionContract.fetch(pda);
+            assert.equal(optionAccount.status.marginCalled !== undefined, true);
+            return { pda, vault, optionAccount };
+        };
+
+        it('Cures a margin call by topping up the vault', async () => {
+            const { pda, vault, optionAccount } = await createMarginCalledTokenOption("AAPL/USDC");
+            const topUp = initialMargin.sub(optionAccount.sellerMargin);
+
+            try {
+                await program.methods
+                    .topUpMargin(topUp)
+                    .accountsPartial({
+                        option: pda,
+                        depositor: seller.publicKey,
+                    })
+                    .signers([seller])
+                    .rpc();
+
+                assert.fail("Should have thrown error for a native top up of a token option");
+            } catch (error: any) {
+                assert.include(error.toString(), "TokenCollateralOption");
+            }
+
+            await program.methods
+                .topUpTokenMargin(topUp)
+                .accountsPartial({
+                    option: pda,
+                    collateralMint: usdcMint,
+                    vault,
+                    depositorTokenAccount: sellerTokenAccount,
+                    depositor: seller.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([seller])
+                .rpc();
+
+            const optionAfter = await program.account.optionContract.fetch(pda);
+            assert.equal(optionAfter.status.owned !== undefined, true);
+            assert.equal(optionAfter.sellerMargin.toNumber(), initialMargin.toNumber());
+            assert.equal(await tokenBalance(vault), 2 * initialMargin.toNumber() + topUp.toNumber());
+        });
+
+        it('Closes out a margin-called option from the vault', async () => {
+            const { pda, vault, optionAccount } = await createMarginCalledTokenOption("AAPL/USDC");
+            const buyerBefore = await tokenBalance(buyerTokenAccount);
+            const sellerBefore = await tokenBalance(sellerTokenAccount);
+
+            await program.methods
+                .closeTokenMarginCalled()
+                .accountsPartial({
+                    option: pda,
+                    collateralMint: usdcMint,
+                    vault,
+                    ownerTokenAccount: buyerTokenAccount,
+                    sellerTokenAccount,
+                    owner: buyer.publicKey,
+                    seller: seller.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            assert.equal(await tokenBalance(buyerTokenAccount) - buyerBefore, optionAccount.buyerMargin.toNumber());
+            assert.equal(await tokenBalance(sellerTokenAccount) - sellerBefore, optionAccount.sellerMargin.toNumber());
+            assert.isNull(await connection.getAccountInfo(vault));
+            assert.isNull(await connection.getAccountInfo(pda));
+        });
+
+        it('Returns both margins from the vault when an option expires out of the money', async () => {
+            // Initiated 40 days ago, so the 30-day option expired ten days ago
+            const initiation = Math.floor(Date.now() / 1000) - 40 * 24 * 60 * 60;
+            const { pda, vault } = await createTokenOption("AAPL/USDC", initiation, true);
+            const buyerBefore = await tokenBalance(buyerTokenAccount);
+            const sellerBefore = await tokenBalance(sellerTokenAccount);
+
+            // AAPL $150 / USDC $1 is below the 200 USDC strike
+            await publishPrices(new anchor.BN(150_000_000), new anchor.BN(1_000_000));
+            await program.methods
+                .expireTokenOption()
+                .accountsPartial({
+                    option: pda,
+                    collateralMint: usdcMint,
+                    vault,
+                    ownerTokenAccount: buyerTokenAccount,
+                    sellerTokenAccount,
+                    owner: buyer.publicKey,
+                    seller: seller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: usdcFeed,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            const optionAccount = await program.account.optionContract.fetch(pda);
+            assert.equal(optionAccount.status.expired !== undefined, true);
+            assert.equal(optionAccount.buyerMargin.toNumber(), 0);
+            assert.equal(optionAccount.sellerMargin.toNumber(), 0);
+            assert.equal(await tokenBalance(buyerTokenAccount) - buyerBefore, initialMargin.toNumber());
+            assert.equal(await tokenBalance(sellerTokenAccount) - sellerBefore, initialMargin.toNumber());
+            assert.isNull(await connection.getAccountInfo(vault));
+        });
+
+        it('Pays tokens donated to the vault to the seller instead of failing to close it', async () => {
+            const initiation = Math.floor(Date.now() / 1000) - 40 * 24 * 60 * 60;
+            const { pda, vault } = await createTokenOption("AAPL/USDC", initiation, true);
+            const buyerBefore = await tokenBalance(buyerTokenAccount);
+            const sellerBefore = await tokenBalance(sellerTokenAccount);
+
+            // Anyone can send the mint to the vault; a non-zero balance would block close_account
+            await mintTo(connection, publisher, usdcMint, vault, publisher, 1);
+
+            await publishPrices(new anchor.BN(150_000_000), new anchor.BN(1_000_000));
+            await program.methods
+                .expireTokenOption()
+                .accountsPartial({
+                    option: pda,
+                    collateralMint: usdcMint,
+                    vault,
+                    ownerTokenAccount: buyerTokenAccount,
+                    sellerTokenAccount,
+                    owner: buyer.publicKey,
+                    seller: seller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: usdcFeed,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            assert.equal(await tokenBalance(buyerTokenAccount) - buyerBefore, initialMargin.toNumber());
+            assert.equal(await tokenBalance(sellerTokenAccount) - sellerBefore, initialMargin.toNumber() + 1);
+            assert.isNull(await connection.getAccountInfo(vault));
+        });
+
+        it('Auto-exercises from the vault and pays the keeper bounty in tokens', async () => {
+            const initiation = Math.floor(Date.now() / 1000) - 40 * 24 * 60 * 60;
+            const { pda, vault } = await createTokenOption("AAPL/USDC", initiation, true);
+            const keeperTokenAccount = await createAccount(connection, publisher, usdcMint, publisher.publicKey);
+            const keeperBounty = (await program.account.optionContract.fetch(pda)).keeperBounty.toNumber();
+            const buyerBefore = await tokenBalance(buyerTokenAccount);
+
+            // AAPL $210 / USDC $1 -> 10 USDC intrinsic
+            await publishPrices(new anchor.BN(210_000_000), new anchor.BN(1_000_000));
+            await program.methods
+                .autoExerciseTokenOption()
+                .accountsPartial({
+                    option: pda,
+                    collateralMint: usdcMint,
+                    vault,
+                    ownerTokenAccount: buyerTokenAccount,
+                    sellerTokenAccount,
+                    keeperTokenAccount,
+                    owner: buyer.publicKey,
+                    seller: seller.publicKey,
+                    assetPriceFeed: assetFeed,
+                    solPriceFeed: usdcFeed,
+                    keeper: publisher.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([publisher])
+                .rpc();
+
+            assert.equal(await tokenBalance(keeperTokenAccount), keeperBounty);
+            assert.equal(await tokenBalance(buyerTokenAccount) - buyerBefore, initialMargin.toNumber() + 10 * USDC - keeperBounty);
+            assert.isNull(await connection.getAccountInfo(vault));
+        });
+
+        it('Closes a delisted option together with its vault', async () => {
+            const { pda, vault } = await createTokenOption("AAPL/USDC", Math.floor(Date.now() / 1000), false);
+
+            await program.methods
+                .delistOption()
+                .accountsPartial({
+                    option: pda,
+                    seller: seller.publicKey,
+                })
+                .signers([seller])
+                .rpc();
+
+            // A donation to the never-funded vault is swept to the seller on close
+            await mintTo(connection, publisher, usdcMint, vault, publisher, 1);
+            const sellerBefore = await tokenBalance(sellerTokenAccount);
+
+            await program.methods
+                .closeOption()
+                .accountsPartial({
+                    option: pda,
+                    vault,
+                    seller: seller.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    collateralMint: usdcMint,
+                    sellerTokenAccount,
+                })
+                .signers([seller])
+                .rpc();
+
+            assert.equal(await tokenBalance(sellerTokenAccount) - sellerBefore, 1);
+            assert.isNull(await connection.getAccountInfo(vault));
+            assert.isNull(await connection.getAccountInfo(pda));
+        });
+    });
+});
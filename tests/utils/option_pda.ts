@@ -0,0 +1,45 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program, web3 } from '@coral-xyz/anchor';
+import { Escrow } from '../../target/types/escrow';
+
+/**
+ * Test helpers for deriving option contract addresses.
+ *
+ * Options are keyed by seller and series id; each seller's `SellerState`
+ * counter holds the next unused series id (0 before their first option).
+ */
+
+export function findSellerStatePda(
+    program: Program<Escrow>,
+    seller: web3.PublicKey
+): web3.PublicKey {
+    const [pda] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("seller"), seller.toBuffer()],
+        program.programId
+    );
+    return pda;
+}
+
+export function findOptionPda(
+    program: Program<Escrow>,
+    seller: web3.PublicKey,
+    seriesId: anchor.BN
+): web3.PublicKey {
+    const [pda] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("option"), seller.toBuffer(), seriesId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+    );
+    return pda;
+}
+
+// Derives the address the seller's next option will be created at
+export async function findNextOptionPda(
+    program: Program<Escrow>,
+    seller: web3.PublicKey
+): Promise<[web3.PublicKey, anchor.BN]> {
+    const sellerState = await program.account.sellerState.fetchNullable(
+        findSellerStatePda(program, seller)
+    );
+    const seriesId = sellerState ? sellerState.nextSeriesId : new anchor.BN(0);
+    return [findOptionPda(program, seller, seriesId), seriesId];
+}
@@ -0,0 +1,93 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program, web3 } from '@coral-xyz/anchor';
+import { Escrow } from '../../target/types/escrow';
+
+/**
+ * Test helpers for the escrow program's oracle price feeds.
+ *
+ * Feeds publish USD prices with 6 decimals (exponent -6), so the same
+ * `toPrice` values used throughout the suites can be published directly.
+ * Options only accept feeds from the program's configured oracle authority,
+ * so each suite points the config at its own publisher before creating options.
+ */
+
+export const PRICE_EXPONENT = -6;
+
+// Creates a price feed owned by the given publisher authority
+export async function createPriceFeed(
+    program: Program<Escrow>,
+    publisher: web3.Keypair,
+    symbol: string
+): Promise<web3.PublicKey> {
+    const [feedPda] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("price_feed"), publisher.publicKey.toBuffer(), Buffer.from(symbol)],
+        program.programId
+    );
+
+    await program.methods
+        .initializePriceFeed(symbol, PRICE_EXPONENT)
+        .accountsPartial({
+            priceFeed: feedPda,
+            authority: publisher.publicKey,
+        })
+        .signers([publisher])
+        .rpc();
+
+    return feedPda;
+}
+
+// Publishes a price to a feed, stamped with the cluster's current time
+export async function publishPrice(
+    program: Program<Escrow>,
+    publisher: web3.Keypair,
+    feed: web3.PublicKey,
+    price: anchor.BN,
+    confidence: anchor.BN = new anchor.BN(0)
+): Promise<void> {
+    const connection = program.provider.connection;
+    const blockTime = await connection.getBlockTime(await connection.getSlot());
+    const publishTime = new anchor.BN(blockTime ?? Math.floor(Date.now() / 1000));
+
+    await program.methods
+        .updatePriceFeed(price, confidence, publishTime)
+        .accountsPartial({
+            priceFeed: feed,
+            authority: publisher.publicKey,
+        })
+        .signers([publisher])
+        .rpc();
+}
+
+// Points the oracle config at a publisher, creating the config on first use
+// The provider wallet deployed the program, so it is the upgrade authority that manages the config
+export async function configureOracleAuthority(
+    program: Program<Escrow>,
+    publisher: web3.PublicKey
+): Promise<void> {
+    const [configPda] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("oracle_config")],
+        program.programId
+    );
+    const config = await program.account.oracleConfig.fetchNullable(configPda);
+
+    if (config === null) {
+        const [programData] = web3.PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            new web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+        );
+        await program.methods
+            .initializeOracleConfig(publisher)
+            .accountsPartial({
+                admin: program.provider.publicKey,
+                programData,
+            })
+            .rpc();
+    } else if (!config.authority.equals(publisher)) {
+        await program.methods
+            .setOracleAuthority(publisher)
+            .accountsPartial({
+                admin: program.provider.publicKey,
+            })
+            .rpc();
+    }
+}
@@ -0,0 +1,439 @@
+//! Fixed-point option pricing shared by the escrow program and off-chain tools
+//!
+//! Values use 9 decimal places (1.0 = 1_000_000_000), matching the program's
+//! asset/SOL ratio units. Every loop has a fixed upper bound so the compute
+//! cost on-chain is bounded regardless of input.
+#![cfg_attr(not(test), no_std)]
+
+pub mod ratio;
+pub use ratio::{ratio, Rounding};
+pub mod settlement;
+
+/// 1.0 in fixed point (9 decimals)
+pub const SCALE: i128 = 1_000_000_000;
+
+/// Seconds in a pricing year (365 days)
+pub const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
+const LN_2_WIDE: i128 = 693_147_180_559_945_309; // ln(2) to 18 decimals for range reduction
+const INV_SQRT_2PI: i128 = 398_942_280; // 1 / sqrt(2 * pi)
+const MAX_EXP_INPUT: i128 = 40 * SCALE; // e^40 still fits comfortably in i128
+const MAX_CDF_INPUT: i128 = 10 * SCALE; // N(x) is 0 or 1 to 9 decimals beyond this
+const SERIES_TERMS: u32 = 24; // Upper bound on Taylor/atanh series terms
+
+/// Errors from fixed-point arithmetic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    /// An intermediate value did not fit
+    Overflow,
+    /// Input outside the function's domain (e.g. ln of a non-positive number)
+    InvalidInput,
+}
+
+/// Option type, matching `OptionContract.option_type` (0: Call, 1: Put)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+impl OptionKind {
+    /// Convert the program's option_type byte
+    pub fn from_u8(option_type: u8) -> Option<Self> {
+        match option_type {
+            0 => Some(OptionKind::Call),
+            1 => Some(OptionKind::Put),
+            _ => None,
+        }
+    }
+}
+
+/// Inputs to the Black-Scholes model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PricingInput {
+    pub spot: u64,           // Current asset/SOL ratio (1e9 = 1 SOL per asset unit)
+    pub strike: u64,         // Strike in the same units as spot
+    pub time_to_expiry: i64, // Seconds until expiry
+    pub volatility: u64,     // Annualized volatility (1e9 = 100%)
+    pub rate: i64,           // Annualized risk-free rate (1e9 = 100%)
+}
+
+/// Multiply two fixed-point values
+pub fn mul(a: i128, b: i128) -> Result<i128, MathError> {
+    a.checked_mul(b)
+        .map(|product| product / SCALE)
+        .ok_or(MathError::Overflow)
+}
+
+/// Divide two fixed-point values
+pub fn div(a: i128, b: i128) -> Result<i128, MathError> {
+    if b == 0 {
+        return Err(MathError::InvalidInput);
+    }
+    a.checked_mul(SCALE)
+        .map(|scaled| scaled / b)
+        .ok_or(MathError::Overflow)
+}
+
+/// Natural logarithm of a positive fixed-point value
+/// Reduces x to m * 2^k with m in [1, 2) and sums the atanh series for ln(m)
+pub fn ln(x: i128) -> Result<i128, MathError> {
+    if x <= 0 {
+        return Err(MathError::InvalidInput);
+    }
+
+    let mut m = x;
+    let mut k: i128 = 0;
+    // At most 127 halvings or doublings for any positive i128
+    while m >= 2 * SCALE {
+        m >>= 1;
+        k += 1;
+    }
+    while m < SCALE {
+        m <<= 1;
+        k -= 1;
+    }
+
+    // ln(m) = 2 * atanh(z) with z = (m - 1) / (m + 1) in [0, 1/3)
+    let z = div(m - SCALE, m + SCALE)?;
+    let z2 = mul(z, z)?;
+    let mut term = z;
+    let mut sum = z;
+    for n in 1..SERIES_TERMS as i128 {
+        term = mul(term, z2)?;
+        if term == 0 {
+            break;
+        }
+        sum += term / (2 * n + 1);
+    }
+
+    Ok(k * LN_2_WIDE / SCALE + 2 * sum)
+}
+
+/// Exponential of a fixed-point value
+/// Reduces x to k * ln(2) + r with r in [0, ln 2) and sums the Taylor series for e^r
+pub fn exp(x: i128) -> Result<i128, MathError> {
+    if x > MAX_EXP_INPUT {
+        return Err(MathError::Overflow);
+    }
+    if x < -MAX_EXP_INPUT {
+        return Ok(0);
+    }
+
+    // Reduce at 18 decimals so the error in k * ln(2) does not grow with k
+    let wide_x = x * SCALE;
+    let k = wide_x.div_euclid(LN_2_WIDE);
+    let r = (wide_x - k * LN_2_WIDE) / SCALE;
+
+    let mut term = SCALE;
+    let mut sum = SCALE;
+    for n in 1..=SERIES_TERMS as i128 {
+        term = mul(term, r)? / n;
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+
+    if k >= 0 {
+        sum.checked_shl(k as u32).ok_or(MathError::Overflow)
+    } else {
+        Ok(sum >> (-k) as u32)
+    }
+}
+
+/// Square root of a non-negative fixed-point value
+pub fn sqrt(x: i128) -> Result<i128, MathError> {
+    if x < 0 {
+        return Err(MathError::InvalidInput);
+    }
+    let scaled = x.checked_mul(SCALE).ok_or(MathError::Overflow)? as u128;
+    Ok(isqrt(scaled) as i128)
+}
+
+// Integer square root by Newton's method, starting above the root
+// Converges in well under 128 iterations for any u128
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let bits = 128 - n.leading_zeros();
+    let mut x = 1u128 << bits.div_ceil(2);
+    for _ in 0..128 {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            break;
+        }
+        x = next;
+    }
+    x
+}
+
+/// Standard normal probability density
+pub fn norm_pdf(x: i128) -> Result<i128, MathError> {
+    let x = x.clamp(-MAX_CDF_INPUT, MAX_CDF_INPUT);
+    let exponent = -mul(x, x)? / 2;
+    mul(exp(exponent)?, INV_SQRT_2PI)
+}
+
+/// Standard normal cumulative distribution
+/// Abramowitz & Stegun 26.2.17, absolute error below 7.5e-8
+pub fn norm_cdf(x: i128) -> Result<i128, MathError> {
+    if x >= MAX_CDF_INPUT {
+        return Ok(SCALE);
+    }
+    if x <= -MAX_CDF_INPUT {
+        return Ok(0);
+    }
+
+    const P: i128 = 231_641_900;
+    const B: [i128; 5] = [
+        319_381_530,
+        -356_563_782,
+        1_781_477_937,
+        -1_821_255_978,
+        1_330_274_429,
+    ];
+
+    let abs_x = x.abs();
+    let t = div(SCALE, SCALE + mul(P, abs_x)?)?;
+
+    // Horner evaluation of b1*t + b2*t^2 + ... + b5*t^5
+    let mut poly = 0;
+    for b in B.iter().rev() {
+        poly = mul(poly + b, t)?;
+    }
+
+    let upper_tail = mul(norm_pdf(abs_x)?, poly)?;
+    if x >= 0 {
+        Ok(SCALE - upper_tail)
+    } else {
+        Ok(upper_tail)
+    }
+}
+
+/// Convert seconds to a fixed-point fraction of a year
+pub fn year_fraction(seconds: i64) -> Result<i128, MathError> {
+    div(seconds as i128, SECONDS_PER_YEAR as i128)
+}
+
+/// Intrinsic value max(spot - strike, 0) for calls or max(strike - spot, 0) for puts
+pub fn intrinsic_value(kind: OptionKind, spot: u64, strike: u64) -> u64 {
+    match kind {
+        OptionKind::Call => spot.saturating_sub(strike),
+        OptionKind::Put => strike.saturating_sub(spot),
+    }
+}
+
+/// Black-Scholes d1 and d2 terms
+/// spot, strike, t (years), volatility and rate are fixed-point values; volatility and t must be positive
+pub fn d1_d2(
+    spot: i128,
+    strike: i128,
+    t: i128,
+    volatility: i128,
+    rate: i128,
+) -> Result<(i128, i128), MathError> {
+    let sigma_sqrt_t = mul(volatility, sqrt(t)?)?;
+    let drift = mul(rate + mul(volatility, volatility)? / 2, t)?;
+    let d1 = div(ln(div(spot, strike)?)? + drift, sigma_sqrt_t)?;
+    Ok((d1, d1 - sigma_sqrt_t))
+}
+
+/// Black-Scholes price of a European option on the asset/SOL ratio
+/// Returns the premium per asset unit in the same units as spot and strike.
+/// Expired options are worth their intrinsic value and zero volatility
+/// gives the discounted forward intrinsic value
+pub fn black_scholes_price(kind: OptionKind, input: &PricingInput) -> Result<u64, MathError> {
+    if input.spot == 0 || input.strike == 0 {
+        return Err(MathError::InvalidInput);
+    }
+    if input.time_to_expiry <= 0 {
+        return Ok(intrinsic_value(kind, input.spot, input.strike));
+    }
+
+    let spot = input.spot as i128;
+    let strike = input.strike as i128;
+    let volatility = input.volatility as i128;
+    let rate = input.rate as i128;
+
+    let t = year_fraction(input.time_to_expiry)?;
+    let discounted_strike = mul(strike, exp(-mul(rate, t)?)?)?;
+
+    let price = if volatility == 0 {
+        match kind {
+            OptionKind::Call => spot - discounted_strike,
+            OptionKind::Put => discounted_strike - spot,
+        }
+    } else {
+        let (d1, d2) = d1_d2(spot, strike, t, volatility, rate)?;
+
+        match kind {
+            OptionKind::Call => {
+                mul(spot, norm_cdf(d1)?)? - mul(discounted_strike, norm_cdf(d2)?)?
+            }
+            OptionKind::Put => {
+                mul(discounted_strike, norm_cdf(-d2)?)? - mul(spot, norm_cdf(-d1)?)?
+            }
+        }
+    };
+
+    u64::try_from(price.max(0)).map_err(|_| MathError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const F: f64 = SCALE as f64;
+
+    fn to_fixed(x: f64) -> i128 {
+        (x * F).round() as i128
+    }
+
+    fn norm_cdf_f64(x: f64) -> f64 {
+        0.5 * libm::erfc(-x / std::f64::consts::SQRT_2)
+    }
+
+    // f64 reference implementation of Black-Scholes
+    fn black_scholes_f64(kind: OptionKind, spot: f64, strike: f64, t: f64, vol: f64, rate: f64) -> f64 {
+        let sigma_sqrt_t = vol * t.sqrt();
+        let d1 = ((spot / strike).ln() + (rate + vol * vol / 2.0) * t) / sigma_sqrt_t;
+        let d2 = d1 - sigma_sqrt_t;
+        let discounted_strike = strike * (-rate * t).exp();
+        match kind {
+            OptionKind::Call => spot * norm_cdf_f64(d1) - discounted_strike * norm_cdf_f64(d2),
+            OptionKind::Put => discounted_strike * norm_cdf_f64(-d2) - spot * norm_cdf_f64(-d1),
+        }
+    }
+
+    #[test]
+    fn test_textbook_values() {
+        // S = 100, K = 100, T = 1y, sigma = 20%, r = 5%: call 10.4506, put 5.5735
+        let input = PricingInput {
+            spot: 100 * SCALE as u64,
+            strike: 100 * SCALE as u64,
+            time_to_expiry: SECONDS_PER_YEAR,
+            volatility: 200_000_000,
+            rate: 50_000_000,
+        };
+        let call = black_scholes_price(OptionKind::Call, &input).unwrap() as f64 / F;
+        let put = black_scholes_price(OptionKind::Put, &input).unwrap() as f64 / F;
+        assert!((call - 10.4506).abs() < 1e-4, "call {}", call);
+        assert!((put - 5.5735).abs() < 1e-4, "put {}", put);
+    }
+
+    #[test]
+    fn test_expired_option_is_intrinsic() {
+        let input = PricingInput {
+            spot: 2_100_000_000,
+            strike: 2_000_000_000,
+            time_to_expiry: 0,
+            volatility: 500_000_000,
+            rate: 0,
+        };
+        assert_eq!(black_scholes_price(OptionKind::Call, &input).unwrap(), 100_000_000);
+        assert_eq!(black_scholes_price(OptionKind::Put, &input).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert_eq!(ln(0), Err(MathError::InvalidInput));
+        assert_eq!(div(SCALE, 0), Err(MathError::InvalidInput));
+        assert_eq!(exp(50 * SCALE), Err(MathError::Overflow));
+        let input = PricingInput {
+            spot: 0,
+            strike: 1,
+            time_to_expiry: 1,
+            volatility: 1,
+            rate: 0,
+        };
+        assert_eq!(black_scholes_price(OptionKind::Call, &input), Err(MathError::InvalidInput));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_ln_matches_f64(x in 0.001f64..1_000_000.0) {
+            let expected = x.ln();
+            let actual = ln(to_fixed(x)).unwrap() as f64 / F;
+            prop_assert!((actual - expected).abs() < 1e-7 + expected.abs() * 1e-8);
+        }
+
+        #[test]
+        fn prop_exp_matches_f64(x in -20.0f64..20.0) {
+            let expected = x.exp();
+            let actual = exp(to_fixed(x)).unwrap() as f64 / F;
+            prop_assert!((actual - expected).abs() < 1e-8 + expected * 1e-8);
+        }
+
+        #[test]
+        fn prop_sqrt_matches_f64(x in 0.0f64..1_000_000.0) {
+            let expected = x.sqrt();
+            let actual = sqrt(to_fixed(x)).unwrap() as f64 / F;
+            prop_assert!((actual - expected).abs() < 1e-8 + expected * 1e-9);
+        }
+
+        #[test]
+        fn prop_norm_cdf_matches_f64(x in -12.0f64..12.0) {
+            let expected = norm_cdf_f64(x);
+            let actual = norm_cdf(to_fixed(x)).unwrap() as f64 / F;
+            prop_assert!((actual - expected).abs() < 1e-7);
+        }
+
+        #[test]
+        fn prop_black_scholes_matches_f64(
+            is_call in any::<bool>(),
+            spot in 0.1f64..1_000.0,
+            moneyness in 0.5f64..2.0,
+            days in 1.0f64..730.0,
+            vol in 0.05f64..2.0,
+            rate in -0.05f64..0.2,
+        ) {
+            let kind = if is_call { OptionKind::Call } else { OptionKind::Put };
+            let strike = spot * moneyness;
+            let seconds = (days * 86_400.0) as i64;
+            let t = seconds as f64 / SECONDS_PER_YEAR as f64;
+            let input = PricingInput {
+                spot: to_fixed(spot) as u64,
+                strike: to_fixed(strike) as u64,
+                time_to_expiry: seconds,
+                volatility: to_fixed(vol) as u64,
+                rate: to_fixed(rate) as i64,
+            };
+
+            let expected = black_scholes_f64(kind, input.spot as f64 / F, input.strike as f64 / F, t, vol, rate);
+            let actual = black_scholes_price(kind, &input).unwrap() as f64 / F;
+            prop_assert!(
+                (actual - expected).abs() < 1e-6 * (spot + strike),
+                "fixed {} vs f64 {}", actual, expected
+            );
+        }
+
+        #[test]
+        fn prop_put_call_parity(
+            spot in 0.1f64..1_000.0,
+            moneyness in 0.5f64..2.0,
+            days in 1.0f64..730.0,
+            vol in 0.05f64..2.0,
+            rate in 0.0f64..0.2,
+        ) {
+            let strike = spot * moneyness;
+            let seconds = (days * 86_400.0) as i64;
+            let t = seconds as f64 / SECONDS_PER_YEAR as f64;
+            let input = PricingInput {
+                spot: to_fixed(spot) as u64,
+                strike: to_fixed(strike) as u64,
+                time_to_expiry: seconds,
+                volatility: to_fixed(vol) as u64,
+                rate: to_fixed(rate) as i64,
+            };
+
+            // C - P = S - K * e^(-rT)
+            let call = black_scholes_price(OptionKind::Call, &input).unwrap() as f64 / F;
+            let put = black_scholes_price(OptionKind::Put, &input).unwrap() as f64 / F;
+            let forward = input.spot as f64 / F - input.strike as f64 / F * (-rate * t).exp();
+            prop_assert!((call - put - forward).abs() < 1e-6 * (spot + strike));
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Cross rates between two USD prices
+//!
+//! The escrow program and off-chain tools both convert an asset's USD price
+//! into collateral units through `ratio`, so previews and settlement agree to
+//! the lamport.
+
+use crate::MathError;
+
+/// How the remainder of an integer division is resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero (truncate); what the program settles with
+    Down,
+    /// Away from zero whenever there is a remainder
+    Up,
+    /// To nearest, ties away from zero
+    HalfUp,
+    /// To nearest, ties to the even neighbour (banker's rounding)
+    HalfEven,
+}
+
+/// Price of one asset unit in collateral base units
+/// asset_price_usd: Asset price in USD (any fixed scale, e.g. 6 decimals)
+/// quote_price_usd: Collateral price in USD, same scale as asset_price_usd
+/// decimals: Collateral decimals (9 for SOL lamports)
+/// Returns InvalidInput for a zero quote price and Overflow if the result exceeds u64
+pub fn ratio(asset_price_usd: u64, quote_price_usd: u64, decimals: u8, rounding: Rounding) -> Result<u64, MathError> {
+    if quote_price_usd == 0 {
+        return Err(MathError::InvalidInput);
+    }
+    let scale = 10u128.checked_pow(decimals as u32).ok_or(MathError::Overflow)?;
+    let numerator = (asset_price_usd as u128).checked_mul(scale).ok_or(MathError::Overflow)?;
+    let divisor = quote_price_usd as u128;
+    let quotient = div_round(numerator, divisor, rounding);
+    u64::try_from(quotient).map_err(|_| MathError::Overflow)
+}
+
+/// Divide with an explicit rounding mode; divisor must be non-zero
+pub fn div_round(numerator: u128, divisor: u128, rounding: Rounding) -> u128 {
+    let quotient = numerator / divisor;
+    let remainder = numerator % divisor;
+    // remainder < divisor, so comparing against divisor - remainder avoids doubling overflow
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder > 0,
+        Rounding::HalfUp => remainder > 0 && remainder >= divisor - remainder,
+        Rounding::HalfEven => {
+            remainder > divisor - remainder || (remainder == divisor - remainder && quotient % 2 == 1)
+        }
+    };
+    quotient + round_up as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_aapl_sol_ratio() {
+        // $225.50 / $150.00 = 1.503333333... SOL
+        assert_eq!(ratio(225_500_000, 150_000_000, 9, Rounding::Down), Ok(1_503_333_333));
+        assert_eq!(ratio(225_500_000, 150_000_000, 9, Rounding::Up), Ok(1_503_333_334));
+        assert_eq!(ratio(225_500_000, 150_000_000, 9, Rounding::HalfUp), Ok(1_503_333_333));
+
+        // USDC collateral with 6 decimals
+        assert_eq!(ratio(210_000_000, 1_000_000, 6, Rounding::Down), Ok(210_000_000));
+    }
+
+    #[test]
+    fn test_ties() {
+        // 1 / 8 at 1 decimal = 1.25 units
+        assert_eq!(ratio(1, 8, 1, Rounding::Down), Ok(1));
+        assert_eq!(ratio(1, 8, 1, Rounding::HalfUp), Ok(1));
+        assert_eq!(ratio(1, 4, 1, Rounding::HalfUp), Ok(3)); // 2.5
+        assert_eq!(ratio(1, 4, 1, Rounding::HalfEven), Ok(2));
+        assert_eq!(ratio(3, 4, 1, Rounding::HalfEven), Ok(8)); // 7.5
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(ratio(1, 0, 9, Rounding::Down), Err(MathError::InvalidInput));
+        assert_eq!(ratio(u64::MAX, 1, 9, Rounding::Down), Err(MathError::Overflow));
+        assert_eq!(ratio(1, 1, 39, Rounding::Down), Err(MathError::Overflow));
+        assert_eq!(ratio(u64::MAX, 1, 0, Rounding::Down), Ok(u64::MAX));
+        assert_eq!(ratio(u64::MAX, 2, 0, Rounding::Up), Ok(u64::MAX / 2 + 1));
+    }
+
+    proptest! {
+        // Matches the program's original inline (asset * 10^decimals) / sol in u128
+        #[test]
+        fn prop_down_matches_inline_formula(asset in 1u64..10_000_000_000, sol in 1u64..1_000_000_000_000, decimals in 0u8..=9) {
+            let inline = (asset as u128) * 10u128.pow(decimals as u32) / (sol as u128);
+            prop_assert_eq!(ratio(asset, sol, decimals, Rounding::Down), Ok(inline as u64));
+        }
+
+        #[test]
+        fn prop_rounding_modes_bracket_exact(asset in 0u64..u64::MAX / 1_000_000_000, sol in 1u64..u64::MAX) {
+            let down = ratio(asset, sol, 9, Rounding::Down).unwrap();
+            let up = ratio(asset, sol, 9, Rounding::Up).unwrap();
+            let half_up = ratio(asset, sol, 9, Rounding::HalfUp).unwrap();
+            let half_even = ratio(asset, sol, 9, Rounding::HalfEven).unwrap();
+            prop_assert!(up - down <= 1);
+            prop_assert!(half_up == down || half_up == up);
+            prop_assert!(half_even == down || half_even == up);
+        }
+    }
+}
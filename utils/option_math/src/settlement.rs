@@ -0,0 +1,229 @@
+//! Variation margin and exercise settlement
+//!
+//! The escrow program settles through these functions and the off-chain
+//! backtester replays contracts through them, so a replay moves exactly the
+//! lamports the program would.
+
+use crate::{MathError, OptionKind};
+
+/// Margin call threshold as a percentage of initial margin
+pub const MARGIN_CALL_THRESHOLD: u64 = 20;
+
+/// Collateral each party holds in the option account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Margins {
+    pub buyer: u64,
+    pub seller: u64,
+    pub accumulated_variation: i64, // Net variation paid to the buyer (negative: to the seller)
+}
+
+/// Side of the contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Party {
+    Buyer,
+    Seller,
+}
+
+/// Scale a per-unit amount by the contract's notional
+/// amount * contract_size * quantity, computed in u128 and checked to fit in u64
+pub fn scale_by_notional(amount: u64, contract_size: u64, quantity: u64) -> Result<u64, MathError> {
+    let scaled = (amount as u128)
+        .checked_mul(contract_size as u128)
+        .ok_or(MathError::Overflow)?
+        .checked_mul(quantity as u128)
+        .ok_or(MathError::Overflow)?;
+    u64::try_from(scaled).map_err(|_| MathError::Overflow)
+}
+
+/// Intrinsic value of the whole contract at a ratio
+/// max(ratio - strike, 0) for calls or max(strike - ratio, 0) for puts, scaled by notional
+pub fn settlement_value(
+    kind: OptionKind,
+    final_ratio: u64,
+    strike: u64,
+    contract_size: u64,
+    quantity: u64,
+) -> Result<u64, MathError> {
+    let intrinsic = match kind {
+        OptionKind::Call => final_ratio.saturating_sub(strike),
+        OptionKind::Put => strike.saturating_sub(final_ratio),
+    };
+    scale_by_notional(intrinsic, contract_size, quantity)
+}
+
+/// Variation owed for a move from reference_ratio to current_ratio
+/// Marks the option to its intrinsic value, so a long option never pays out
+/// more than it has received. Returns (buyer_gain, seller_gain); at most one is non-zero
+pub fn pnl(
+    kind: OptionKind,
+    current_ratio: u64,
+    reference_ratio: u64,
+    strike: u64,
+    contract_size: u64,
+    quantity: u64,
+) -> Result<(u64, u64), MathError> {
+    let previous_value = settlement_value(kind, reference_ratio, strike, contract_size, quantity)?;
+    let current_value = settlement_value(kind, current_ratio, strike, contract_size, quantity)?;
+    if current_value > previous_value {
+        Ok((current_value - previous_value, 0))
+    } else {
+        Ok((0, previous_value - current_value))
+    }
+}
+
+/// Margin below which a party is margin called
+/// threshold_percent: Percentage of initial margin (MARGIN_CALL_THRESHOLD on-chain)
+pub fn margin_threshold(initial_margin: u64, threshold_percent: u64) -> Result<u64, MathError> {
+    initial_margin
+        .checked_mul(threshold_percent)
+        .map(|scaled| scaled / 100)
+        .ok_or(MathError::Overflow)
+}
+
+/// Move one settlement's variation between the margins
+/// A transfer that would leave the paying party at or below threshold is capped
+/// so they keep exactly threshold, and that party is returned as margin called
+pub fn apply_variation(
+    margins: &mut Margins,
+    buyer_gain: u64,
+    seller_gain: u64,
+    threshold: u64,
+) -> Result<Option<Party>, MathError> {
+    if buyer_gain > 0 {
+        let (transfer, margin_called) = capped_transfer(margins.seller, buyer_gain, threshold);
+        margins.buyer = margins.buyer.checked_add(transfer).ok_or(MathError::Overflow)?;
+        margins.seller = margins.seller.checked_sub(transfer).ok_or(MathError::Overflow)?;
+        margins.accumulated_variation = margins
+            .accumulated_variation
+            .checked_add(i64::try_from(transfer).map_err(|_| MathError::Overflow)?)
+            .ok_or(MathError::Overflow)?;
+        if margin_called {
+            margins.seller = threshold;
+            return Ok(Some(Party::Seller));
+        }
+    } else if seller_gain > 0 {
+        let (transfer, margin_called) = capped_transfer(margins.buyer, seller_gain, threshold);
+        margins.seller = margins.seller.checked_add(transfer).ok_or(MathError::Overflow)?;
+        margins.buyer = margins.buyer.checked_sub(transfer).ok_or(MathError::Overflow)?;
+        margins.accumulated_variation = margins
+            .accumulated_variation
+            .checked_sub(i64::try_from(transfer).map_err(|_| MathError::Overflow)?)
+            .ok_or(MathError::Overflow)?;
+        if margin_called {
+            margins.buyer = threshold;
+            return Ok(Some(Party::Buyer));
+        }
+    }
+    Ok(None)
+}
+
+// Helper function to cap a variation payment at what the payer holds above threshold
+// Returns (amount actually transferred, whether the payer is margin called)
+fn capped_transfer(payer_margin: u64, owed: u64, threshold: u64) -> (u64, bool) {
+    if owed >= payer_margin || payer_margin.saturating_sub(owed) <= threshold {
+        (payer_margin.saturating_sub(threshold), true)
+    } else {
+        (owed, false)
+    }
+}
+
+/// Split the margins at exercise or expiry
+/// Daily settlement has already moved accumulated_variation to the buyer (negative: to the seller),
+/// so only the difference between the intrinsic value and that amount is still owed.
+/// Returns (owner_amount, seller_amount, shortfall)
+pub fn exercise_split(
+    settlement_value: u64,
+    accumulated_variation: i64,
+    buyer_margin: u64,
+    seller_margin: u64,
+) -> Result<(u64, u64, u64), MathError> {
+    let net_payout = settlement_value as i128 - accumulated_variation as i128;
+    if net_payout >= 0 {
+        // Seller can only pay out what is left in their margin; the rest is a shortfall
+        let owed = u64::try_from(net_payout).map_err(|_| MathError::Overflow)?;
+        let payout = owed.min(seller_margin);
+        let owner_amount = buyer_margin.checked_add(payout).ok_or(MathError::Overflow)?;
+        Ok((owner_amount, seller_margin - payout, owed - payout))
+    } else {
+        // Buyer received more variation than the option is worth and returns the excess
+        let owed = u64::try_from(-net_payout).map_err(|_| MathError::Overflow)?;
+        let refund = owed.min(buyer_margin);
+        let seller_amount = seller_margin.checked_add(refund).ok_or(MathError::Overflow)?;
+        Ok((buyer_margin - refund, seller_amount, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SOL: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_pnl_marks_to_intrinsic_value() {
+        // Call struck at 1.5: 1.5 -> 1.67 pays the buyer 0.17 per unit
+        assert_eq!(pnl(OptionKind::Call, 1_670_000_000, 1_500_000_000, 1_500_000_000, 1, 1), Ok((170_000_000, 0)));
+        // Falling further out of the money moves nothing
+        assert_eq!(pnl(OptionKind::Call, 1_200_000_000, 1_400_000_000, 1_500_000_000, 1, 1), Ok((0, 0)));
+        // Put back from 1.2 to 1.4 pays the seller 0.2 per unit, times 3 units
+        assert_eq!(pnl(OptionKind::Put, 1_400_000_000, 1_200_000_000, 1_500_000_000, 1, 3), Ok((0, 600_000_000)));
+        assert_eq!(scale_by_notional(u64::MAX, 2, 1), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn test_apply_variation_margin_call() {
+        let threshold = margin_threshold(SOL / 10, MARGIN_CALL_THRESHOLD).unwrap();
+        assert_eq!(threshold, 20_000_000);
+
+        let mut margins = Margins { buyer: SOL / 10, seller: SOL / 10, accumulated_variation: 0 };
+        assert_eq!(apply_variation(&mut margins, 50_000_000, 0, threshold), Ok(None));
+        assert_eq!(margins, Margins { buyer: 150_000_000, seller: 50_000_000, accumulated_variation: 50_000_000 });
+
+        // Landing exactly on the threshold is already a margin call; the transfer is capped
+        assert_eq!(apply_variation(&mut margins, 30_000_000, 0, threshold), Ok(Some(Party::Seller)));
+        assert_eq!(margins, Margins { buyer: 180_000_000, seller: 20_000_000, accumulated_variation: 80_000_000 });
+
+        let mut margins = Margins { buyer: SOL / 10, seller: SOL / 10, accumulated_variation: 0 };
+        assert_eq!(apply_variation(&mut margins, 0, SOL, threshold), Ok(Some(Party::Buyer)));
+        assert_eq!(margins, Margins { buyer: 20_000_000, seller: 180_000_000, accumulated_variation: -80_000_000 });
+    }
+
+    #[test]
+    fn test_exercise_split() {
+        // Owed 0.5 SOL after 0.2 SOL of variation; seller pays the remaining 0.3
+        assert_eq!(exercise_split(500_000_000, 200_000_000, SOL, SOL), Ok((1_300_000_000, 700_000_000, 0)));
+        // Buyer was paid 0.2 SOL of variation but the option expired worthless
+        assert_eq!(exercise_split(0, 200_000_000, SOL, SOL), Ok((800_000_000, 1_200_000_000, 0)));
+        // Seller cannot cover the payout
+        assert_eq!(exercise_split(3 * SOL, 0, SOL, SOL), Ok((2 * SOL, 0, 2 * SOL)));
+    }
+
+    proptest! {
+        // With both parties at or above threshold, variation only moves collateral between them
+        #[test]
+        fn prop_apply_variation_conserves_margin(
+            buyer in 0u64..10 * SOL,
+            seller in 0u64..10 * SOL,
+            gain in 0u64..20 * SOL,
+            to_buyer in any::<bool>(),
+            percent in 0u64..=100,
+        ) {
+            let threshold = margin_threshold(buyer.min(seller), percent).unwrap();
+            let mut margins = Margins { buyer, seller, accumulated_variation: 0 };
+            let (buyer_gain, seller_gain) = if to_buyer { (gain, 0) } else { (0, gain) };
+            let margin_called = apply_variation(&mut margins, buyer_gain, seller_gain, threshold).unwrap();
+
+            prop_assert_eq!(margins.buyer as u128 + margins.seller as u128, buyer as u128 + seller as u128);
+            prop_assert_eq!(margins.accumulated_variation as i128, margins.buyer as i128 - buyer as i128);
+            match margin_called {
+                Some(Party::Seller) => prop_assert_eq!(margins.seller, threshold),
+                Some(Party::Buyer) => prop_assert_eq!(margins.buyer, threshold),
+                None => {
+                    let payer = if to_buyer { margins.seller } else { margins.buyer };
+                    prop_assert!(gain == 0 || payer > threshold);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Median-of-sources price aggregation
+//!
+//! Queries every configured `PriceSource`, drops failed, stale, future-dated
+//! and outlying quotes, and returns the median with a confidence interval in
+//! the same units as the program's price feed, so a single bad print cannot
+//! move margin settlement.
+
+use crate::{Price, PriceOracleError, PriceSource};
+use chrono::Utc;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+type SourceErrors = Vec<(String, PriceOracleError)>; // Failures keyed by source name
+
+/// Rules for accepting quotes into the median
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AggregatorConfig {
+    pub max_age: i64,            // Quotes older than this many seconds are stale
+    pub max_clock_skew: i64,     // Quotes stamped more than this many seconds ahead of now are rejected
+    pub max_deviation_bps: u64,  // Quotes further than this from the median are outliers
+    pub min_sources: usize,      // Fewest accepted quotes that still yield a price
+}
+
+impl Default for AggregatorConfig {
+    fn default() -> Self {
+        AggregatorConfig {
+            max_age: 60,             // Matches the program's MAX_PRICE_AGE
+            max_clock_skew: 5,
+            max_deviation_bps: 200,  // 2%
+            min_sources: 2,
+        }
+    }
+}
+
+/// Median price agreed by the accepted sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregatedPrice {
+    pub symbol: String,
+    pub price_usd: u64,       // Median of accepted quotes (6 decimals)
+    pub confidence: u64,      // Largest distance of an accepted quote from the median
+    pub timestamp: i64,       // Oldest accepted quote's timestamp
+    pub sources: Vec<String>, // Names of the sources whose quotes were used
+}
+
+/// Combines several named price sources into one median price
+pub struct PriceAggregator {
+    sources: Vec<(String, Box<dyn PriceSource>)>,
+    config: AggregatorConfig,
+}
+
+impl PriceAggregator {
+    pub fn new(config: AggregatorConfig) -> Self {
+        PriceAggregator { sources: Vec::new(), config }
+    }
+
+    /// Add a source; the name is reported in `AggregatedPrice::sources`
+    pub fn with_source(mut self, name: impl Into<String>, source: Box<dyn PriceSource>) -> Self {
+        self.sources.push((name.into(), source));
+        self
+    }
+
+    /// Median spot price across all sources
+    pub fn spot(&self, symbol: &str) -> Result<AggregatedPrice, PriceOracleError> {
+        let (quotes, errors) = self.query(|source| source.spot(symbol));
+        aggregate(symbol, quotes, Utc::now().timestamp(), &self.config).map_err(|error| with_errors(error, errors))
+    }
+
+    /// Median closing price across all sources for a date (YYYY-MM-DD)
+    /// Historical closes are never stale, so only the deviation band and clock skew apply
+    pub fn historical(&self, symbol: &str, date: &str) -> Result<AggregatedPrice, PriceOracleError> {
+        let (quotes, errors) = self.query(|source| source.historical(symbol, date));
+        let config = AggregatorConfig {
+            max_age: i64::MAX,
+            ..self.config
+        };
+        aggregate(symbol, quotes, Utc::now().timestamp(), &config).map_err(|error| with_errors(error, errors))
+    }
+
+    // Helper function to ask every source, keeping each failure by source name
+    fn query(
+        &self,
+        fetch: impl Fn(&dyn PriceSource) -> Result<Price, PriceOracleError>,
+    ) -> (Vec<(String, Price)>, SourceErrors) {
+        let mut quotes = Vec::new();
+        let mut errors = Vec::new();
+        for (name, source) in &self.sources {
+            match fetch(source.as_ref()) {
+                Ok(price) => quotes.push((name.clone(), price)),
+                Err(error) => errors.push((name.clone(), error)),
+            }
+        }
+        (quotes, errors)
+    }
+}
+
+// Helper function to report the failed sources when too few quotes remained
+fn with_errors(error: PriceOracleError, source_errors: SourceErrors) -> PriceOracleError {
+    match error {
+        PriceOracleError::InsufficientSources { symbol, required, available, .. } => {
+            PriceOracleError::InsufficientSources { symbol, required, available, errors: source_errors }
+        }
+        error => error,
+    }
+}
+
+/// Aggregate already-fetched quotes as of `now`
+/// Quotes are filtered by age and clock skew, then by distance from the median of the
+/// fresh quotes, and the median and confidence are recomputed over the survivors
+pub fn aggregate(
+    symbol: &str,
+    quotes: Vec<(String, Price)>,
+    now: i64,
+    config: &AggregatorConfig,
+) -> Result<AggregatedPrice, PriceOracleError> {
+    let fresh: Vec<(String, Price)> = quotes
+        .into_iter()
+        .filter(|(_, price)| {
+            now.saturating_sub(price.timestamp) <= config.max_age
+                && price.timestamp.saturating_sub(now) <= config.max_clock_skew
+        })
+        .collect();
+    let insufficient = |available: usize| PriceOracleError::InsufficientSources {
+        symbol: symbol.to_string(),
+        required: config.min_sources.max(1),
+        available,
+        errors: Vec::new(),
+    };
+    if fresh.len() < config.min_sources.max(1) {
+        return Err(insufficient(fresh.len()));
+    }
+
+    let reference = median(fresh.iter().map(|(_, price)| price.price_usd).collect());
+    // A band wider than u64 (more than 100% of a large reference) accepts every quote
+    let band = reference as u128 * config.max_deviation_bps as u128 / BPS_DENOMINATOR;
+    let band = u64::try_from(band).unwrap_or(u64::MAX);
+    let accepted: Vec<(String, Price)> = fresh
+        .into_iter()
+        .filter(|(_, price)| price.price_usd.abs_diff(reference) <= band)
+        .collect();
+    if accepted.len() < config.min_sources.max(1) {
+        return Err(insufficient(accepted.len()));
+    }
+
+    let price_usd = median(accepted.iter().map(|(_, price)| price.price_usd).collect());
+    let confidence = accepted
+        .iter()
+        .map(|(_, price)| price.price_usd.abs_diff(price_usd))
+        .max()
+        .unwrap_or(0);
+    let timestamp = accepted.iter().map(|(_, price)| price.timestamp).min().unwrap_or(now);
+
+    Ok(AggregatedPrice {
+        symbol: symbol.to_string(),
+        price_usd,
+        confidence,
+        timestamp,
+        sources: accepted.into_iter().map(|(name, _)| name).collect(),
+    })
+}
+
+// Helper function to take the median, averaging (rounded down) the middle pair of an even count
+fn median(mut values: Vec<u64>) -> u64 {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[middle]
+    } else {
+        ((values[middle - 1] as u128 + values[middle] as u128) / 2) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixturePriceSource;
+
+    const NOW: i64 = 1_756_684_800;
+
+    fn quote(name: &str, price_usd: u64, age: i64) -> (String, Price) {
+        (
+            name.to_string(),
+            Price {
+                symbol: "SOL".to_string(),
+                price_usd,
+                timestamp: NOW - age,
+            },
+        )
+    }
+
+    #[test]
+    fn test_rejects_outlier_and_stale_quotes() {
+        let quotes = vec![
+            quote("coingecko", 150_000_000, 10),
+            quote("exchange_a", 150_600_000, 5),
+            quote("exchange_b", 149_700_000, 20),
+            quote("bad_print", 15_000_000, 1),     // Decimal shifted
+            quote("stale", 150_100_000, 3_600),    // An hour old
+        ];
+        let price = aggregate("SOL", quotes, NOW, &AggregatorConfig::default()).unwrap();
+
+        assert_eq!(price.price_usd, 150_000_000);
+        assert_eq!(price.confidence, 600_000);
+        assert_eq!(price.timestamp, NOW - 20);
+        assert_eq!(price.sources, vec!["coingecko", "exchange_a", "exchange_b"]);
+    }
+
+    #[test]
+    fn test_even_count_averages_middle_pair() {
+        let quotes = vec![quote("a", 100_000_000, 0), quote("b", 101_000_000, 0)];
+        let price = aggregate("SOL", quotes, NOW, &AggregatorConfig::default()).unwrap();
+        assert_eq!(price.price_usd, 100_500_000);
+        assert_eq!(price.confidence, 500_000);
+    }
+
+    #[test]
+    fn test_too_few_sources_after_filtering() {
+        // Two sources that disagree by 10% leave nothing inside a 2% band around their median
+        let quotes = vec![quote("a", 100_000_000, 0), quote("b", 110_000_000, 0)];
+        assert!(matches!(
+            aggregate("SOL", quotes, NOW, &AggregatorConfig::default()),
+            Err(PriceOracleError::InsufficientSources { required: 2, available: 0, .. })
+        ));
+
+        let quotes = vec![quote("a", 100_000_000, 0), quote("b", 100_000_000, 900)];
+        assert!(matches!(
+            aggregate("SOL", quotes, NOW, &AggregatorConfig::default()),
+            Err(PriceOracleError::InsufficientSources { available: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_wide_band_on_large_prices_does_not_wrap() {
+        let config = AggregatorConfig {
+            max_deviation_bps: u64::MAX,
+            ..AggregatorConfig::default()
+        };
+        let quotes = vec![quote("a", u64::MAX / 2, 0), quote("b", u64::MAX / 4, 0)];
+        let price = aggregate("SOL", quotes, NOW, &config).unwrap();
+        assert_eq!(price.sources, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_rejects_future_dated_quotes() {
+        // A few seconds of clock skew is tolerated, a quote from ten minutes ahead is not
+        let quotes = vec![quote("a", 100_000_000, -3), quote("b", 100_000_000, -600)];
+        assert!(matches!(
+            aggregate("SOL", quotes, NOW, &AggregatorConfig::default()),
+            Err(PriceOracleError::InsufficientSources { available: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_aggregates_price_sources() {
+        let aggregator = PriceAggregator::new(AggregatorConfig::default())
+            .with_source("fixture_a", Box::new(FixturePriceSource::sample()))
+            .with_source("fixture_b", Box::new(FixturePriceSource::sample()));
+
+        let price = aggregator.historical("AAPL", "2025-08-05").unwrap();
+        assert_eq!(price.price_usd, 228_750_000);
+        assert_eq!(price.confidence, 0);
+        assert_eq!(price.sources, vec!["fixture_a", "fixture_b"]);
+
+        // Sources that fail are skipped rather than failing the aggregate, and reported if too few remain
+        match aggregator.spot("TSLA") {
+            Err(PriceOracleError::InsufficientSources { available: 0, errors, .. }) => {
+                assert_eq!(errors.len(), 2);
+                assert_eq!(errors[0].0, "fixture_a");
+                assert!(matches!(errors[1].1, PriceOracleError::MissingSymbol(_)));
+            }
+            other => panic!("expected insufficient sources, got {:?}", other),
+        }
+    }
+}
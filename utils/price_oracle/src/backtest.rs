@@ -0,0 +1,447 @@
+//! Off-chain replay of an option contract's lifecycle
+//!
+//! Replays `purchase_option`, each `daily_settlement` and the final
+//! `exercise_option` (or `close_margin_called`) over a price series through the
+//! program's own ratio and settlement math, so parameter choices such as the
+//! margin call threshold can be evaluated without a validator.
+
+use crate::pricing::settlement::{self, Margins, Party, MARGIN_CALL_THRESHOLD};
+use crate::pricing::{ratio, MathError, OptionKind, Rounding};
+use crate::simulate::PathPoint;
+use crate::{PriceOracleError, PriceSource, SOL_DECIMALS};
+use std::fmt;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// The contract terms fixed at `initialize_option`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionSpec {
+    pub option_type: u8,     // 0: Call, 1: Put
+    pub strike: u64,         // Strike (asset/collateral ratio in base units)
+    pub premium: u64,        // Premium paid by the buyer to the seller at purchase
+    pub initial_margin: u64, // Margin posted by each party at purchase
+    pub expiry_date: i64,    // Expiry timestamp
+    pub contract_size: u64,  // Asset units per contract
+    pub quantity: u64,       // Number of contracts
+}
+
+/// Program parameters the replay runs under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BacktestConfig {
+    pub margin_call_threshold: u64, // Percentage of initial margin that triggers a margin call
+    pub collateral_decimals: u8,    // 9 for SOL lamports
+    pub settlement_interval: i64,   // Minimum seconds between settlements
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        BacktestConfig {
+            margin_call_threshold: MARGIN_CALL_THRESHOLD,
+            collateral_decimals: SOL_DECIMALS,
+            settlement_interval: SECONDS_PER_DAY,
+        }
+    }
+}
+
+impl BacktestConfig {
+    pub fn with_margin_call_threshold(mut self, threshold_percent: u64) -> Self {
+        self.margin_call_threshold = threshold_percent;
+        self
+    }
+}
+
+/// Asset and collateral prices at one moment, as the two feeds would publish them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Observation {
+    pub timestamp: i64,
+    pub asset_price_usd: u64, // USD with 6 decimals
+    pub sol_price_usd: u64,   // USD with 6 decimals
+}
+
+impl Observation {
+    /// Closing prices for each date (YYYY-MM-DD) from a price source
+    pub fn from_source(
+        source: &dyn PriceSource,
+        asset_symbol: &str,
+        sol_symbol: &str,
+        dates: &[&str],
+    ) -> Result<Vec<Observation>, PriceOracleError> {
+        dates
+            .iter()
+            .map(|date| {
+                let asset = source.historical(asset_symbol, date)?;
+                let sol = source.historical(sol_symbol, date)?;
+                Ok(Observation {
+                    timestamp: asset.timestamp,
+                    asset_price_usd: asset.price_usd,
+                    sol_price_usd: sol.price_usd,
+                })
+            })
+            .collect()
+    }
+}
+
+impl From<&PathPoint> for Observation {
+    fn from(point: &PathPoint) -> Self {
+        Observation {
+            timestamp: point.timestamp,
+            asset_price_usd: point.asset_price_usd,
+            sol_price_usd: point.sol_price_usd,
+        }
+    }
+}
+
+/// State after one `daily_settlement`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettlementDay {
+    pub timestamp: i64,
+    pub ratio: u64,       // Asset/collateral ratio used for the settlement
+    pub buyer_gain: u64,  // Variation owed to the buyer before any cap
+    pub seller_gain: u64, // Variation owed to the seller before any cap
+    pub margins: Margins, // Margins after the transfer
+    pub margin_call: Option<Party>,
+}
+
+/// How the contract ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Exercised (or expired worthless) at the first observation at or after expiry
+    Exercised {
+        timestamp: i64,
+        final_ratio: u64,
+        settlement_value: u64,
+        shortfall: u64, // Intrinsic value the seller's margin could not cover
+    },
+    /// Margin called and closed out with the margins as they stood; no top-ups are modelled
+    MarginCalled { timestamp: i64, party: Party },
+}
+
+/// Everything the replay did, day by day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BacktestReport {
+    pub days: Vec<SettlementDay>,
+    pub outcome: Outcome,
+    pub owner_amount: u64,  // Collateral returned to the buyer at the end
+    pub seller_amount: u64, // Collateral returned to the seller at the end
+    pub buyer_pnl: i128,    // owner_amount - initial_margin - premium
+    pub seller_pnl: i128,   // seller_amount + premium - initial_margin
+}
+
+/// Errors from a replay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BacktestError {
+    /// Option type is not 0 (call) or 1 (put)
+    InvalidOptionType(u8),
+    /// The series needs a purchase observation before expiry
+    NoPurchase,
+    /// No observation at or after expiry to exercise against
+    SeriesEndsBeforeExpiry { last_timestamp: i64, expiry_date: i64 },
+    /// Fixed-point arithmetic failed (including a zero collateral price)
+    Math(MathError),
+}
+
+impl fmt::Display for BacktestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BacktestError::InvalidOptionType(option_type) => write!(f, "invalid option type {}", option_type),
+            BacktestError::NoPurchase => write!(f, "price series has no observation before expiry"),
+            BacktestError::SeriesEndsBeforeExpiry {
+                last_timestamp,
+                expiry_date,
+            } => write!(
+                f,
+                "price series ends at {} before expiry at {}",
+                last_timestamp, expiry_date
+            ),
+            BacktestError::Math(error) => write!(f, "fixed-point math error: {:?}", error),
+        }
+    }
+}
+
+impl std::error::Error for BacktestError {}
+
+impl From<MathError> for BacktestError {
+    fn from(error: MathError) -> Self {
+        BacktestError::Math(error)
+    }
+}
+
+/// Replay a contract bought at the first observation
+/// observations: Prices in time order; the first is the purchase, later ones before
+/// expiry are settled whenever settlement_interval has passed since the last settlement,
+/// and the first one at or after expiry is the exercise price
+pub fn backtest(
+    spec: &OptionSpec,
+    observations: &[Observation],
+    config: &BacktestConfig,
+) -> Result<BacktestReport, BacktestError> {
+    let kind = OptionKind::from_u8(spec.option_type).ok_or(BacktestError::InvalidOptionType(spec.option_type))?;
+    let purchase = observations
+        .first()
+        .filter(|observation| observation.timestamp < spec.expiry_date)
+        .ok_or(BacktestError::NoPurchase)?;
+
+    // purchase_option: both parties post initial margin; the premium goes straight to the seller
+    let mut margins = Margins {
+        buyer: spec.initial_margin,
+        seller: spec.initial_margin,
+        accumulated_variation: 0,
+    };
+    let mut last_settlement_date = purchase.timestamp;
+    let mut last_settlement_price = 0;
+    let threshold = settlement::margin_threshold(spec.initial_margin, config.margin_call_threshold)?;
+
+    let mut days = Vec::new();
+    for observation in &observations[1..] {
+        if observation.timestamp >= spec.expiry_date {
+            // expire_option records the expiry ratio, exercise_option settles against it
+            let final_ratio = collateral_ratio(observation, config)?;
+            let settlement_value =
+                settlement::settlement_value(kind, final_ratio, spec.strike, spec.contract_size, spec.quantity)?;
+            let (owner_amount, seller_amount, shortfall) = settlement::exercise_split(
+                settlement_value,
+                margins.accumulated_variation,
+                margins.buyer,
+                margins.seller,
+            )?;
+            let outcome = Outcome::Exercised {
+                timestamp: observation.timestamp,
+                final_ratio,
+                settlement_value,
+                shortfall,
+            };
+            return Ok(report(spec, days, outcome, owner_amount, seller_amount));
+        }
+        if observation.timestamp < last_settlement_date + config.settlement_interval {
+            continue;
+        }
+
+        // daily_settlement: the first settlement is marked against the strike
+        let current_ratio = collateral_ratio(observation, config)?;
+        let reference_price = if last_settlement_price == 0 {
+            spec.strike
+        } else {
+            last_settlement_price
+        };
+        let (buyer_gain, seller_gain) = settlement::pnl(
+            kind,
+            current_ratio,
+            reference_price,
+            spec.strike,
+            spec.contract_size,
+            spec.quantity,
+        )?;
+        let margin_call = settlement::apply_variation(&mut margins, buyer_gain, seller_gain, threshold)?;
+        last_settlement_date = observation.timestamp;
+        last_settlement_price = current_ratio;
+        days.push(SettlementDay {
+            timestamp: observation.timestamp,
+            ratio: current_ratio,
+            buyer_gain,
+            seller_gain,
+            margins,
+            margin_call,
+        });
+
+        if let Some(party) = margin_call {
+            // close_margin_called returns each party's margin as it stands
+            let outcome = Outcome::MarginCalled {
+                timestamp: observation.timestamp,
+                party,
+            };
+            return Ok(report(spec, days, outcome, margins.buyer, margins.seller));
+        }
+    }
+
+    Err(BacktestError::SeriesEndsBeforeExpiry {
+        last_timestamp: observations.last().map(|observation| observation.timestamp).unwrap_or_default(),
+        expiry_date: spec.expiry_date,
+    })
+}
+
+// Helper function to compute the settlement ratio exactly as the program does
+fn collateral_ratio(observation: &Observation, config: &BacktestConfig) -> Result<u64, MathError> {
+    ratio(
+        observation.asset_price_usd,
+        observation.sol_price_usd,
+        config.collateral_decimals,
+        Rounding::Down,
+    )
+}
+
+fn report(
+    spec: &OptionSpec,
+    days: Vec<SettlementDay>,
+    outcome: Outcome,
+    owner_amount: u64,
+    seller_amount: u64,
+) -> BacktestReport {
+    let premium = spec.premium as i128;
+    let initial_margin = spec.initial_margin as i128;
+    BacktestReport {
+        days,
+        outcome,
+        owner_amount,
+        seller_amount,
+        buyer_pnl: owner_amount as i128 - initial_margin - premium,
+        seller_pnl: seller_amount as i128 + premium - initial_margin,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulate::{PathSimulator, PathSpec};
+    use crate::FixturePriceSource;
+    use chrono::NaiveDate;
+
+    const SOL: u64 = 1_000_000_000;
+    const AUG_1: i64 = 1_754_006_400; // 2025-08-01T00:00:00Z
+    const THIRTY_DAYS: i64 = 30 * SECONDS_PER_DAY;
+
+    fn observation(day: i64, asset: u64, sol: u64) -> Observation {
+        Observation {
+            timestamp: AUG_1 + day * SECONDS_PER_DAY,
+            asset_price_usd: asset,
+            sol_price_usd: sol,
+        }
+    }
+
+    fn call(strike: u64, premium: u64, initial_margin: u64) -> OptionSpec {
+        OptionSpec {
+            option_type: 0,
+            strike,
+            premium,
+            initial_margin,
+            expiry_date: AUG_1 + THIRTY_DAYS,
+            contract_size: 1,
+            quantity: 1,
+        }
+    }
+
+    #[test]
+    fn test_replays_margin_call_scenario() {
+        // The "extreme price movement" case from tests/aapl_historical.ts
+        let spec = call(1_500_000_000, SOL / 5, SOL / 10);
+        let observations = [
+            observation(0, 225_500_000, 150_000_000),
+            observation(1, 225_500_000, 150_000_000),
+            observation(2, 240_000_000, 144_000_000),
+            observation(3, 250_000_000, 133_000_000),
+        ];
+        let report = backtest(&spec, &observations, &BacktestConfig::default()).unwrap();
+
+        assert_eq!(report.days.len(), 2);
+        assert_eq!(report.days[0].ratio, 1_503_333_333);
+        assert_eq!(report.days[0].buyer_gain, 3_333_333);
+        assert_eq!(report.days[0].margins.seller, 96_666_667);
+        assert_eq!(report.days[1].buyer_gain, 163_333_333);
+        assert_eq!(report.days[1].margin_call, Some(Party::Seller));
+        assert_eq!(
+            report.outcome,
+            Outcome::MarginCalled {
+                timestamp: AUG_1 + 2 * SECONDS_PER_DAY,
+                party: Party::Seller
+            }
+        );
+        assert_eq!((report.owner_amount, report.seller_amount), (180_000_000, 20_000_000));
+        assert_eq!((report.buyer_pnl, report.seller_pnl), (-120_000_000, 120_000_000));
+    }
+
+    #[test]
+    fn test_exercise_pays_intrinsic_value() {
+        let fixtures = FixturePriceSource::sample();
+        let dates = [
+            "2025-08-01", "2025-08-05", "2025-08-10", "2025-08-15",
+            "2025-08-20", "2025-08-25", "2025-08-30", "2025-09-01",
+        ];
+        let observations = Observation::from_source(&fixtures, "AAPL", "SOL", &dates).unwrap();
+        let spec = call(1_400_000_000, SOL / 10, SOL);
+        let report = backtest(&spec, &observations, &BacktestConfig::default()).unwrap();
+
+        // 2025-08-31 is expiry, so the last fixture date exercises at 240 / 165
+        assert_eq!(report.days.len(), 6);
+        assert_eq!(
+            report.outcome,
+            Outcome::Exercised {
+                timestamp: AUG_1 + 31 * SECONDS_PER_DAY,
+                final_ratio: 1_454_545_454,
+                settlement_value: 54_545_454,
+                shortfall: 0,
+            }
+        );
+        // Variation plus the exercise split pay the buyer exactly the intrinsic value
+        assert_eq!(report.owner_amount, SOL + 54_545_454);
+        assert_eq!(report.owner_amount + report.seller_amount, 2 * SOL);
+        assert_eq!(report.buyer_pnl, 54_545_454 - (SOL / 10) as i128);
+    }
+
+    #[test]
+    fn test_threshold_sweep_on_gap() {
+        // A 20% gap takes the ratio from 1.5 to 1.8 and leaves the seller 40% of margin
+        let path = PathSimulator::new(
+            3,
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+            31,
+            PathSpec::scripted(225_000_000).with_shock(3, 2_000),
+            PathSpec::scripted(150_000_000),
+        )
+        .run()
+        .unwrap();
+        let observations: Vec<Observation> = path.points.iter().map(Observation::from).collect();
+        let spec = call(1_500_000_000, 0, SOL / 2);
+
+        let default = backtest(&spec, &observations, &BacktestConfig::default()).unwrap();
+        assert!(default.days.iter().all(|day| day.margin_call.is_none()));
+        assert!(matches!(default.outcome, Outcome::Exercised { settlement_value: 300_000_000, .. }));
+
+        let strict = backtest(&spec, &observations, &BacktestConfig::default().with_margin_call_threshold(40)).unwrap();
+        assert_eq!(strict.days.len(), 3);
+        assert_eq!(
+            strict.outcome,
+            Outcome::MarginCalled {
+                timestamp: AUG_1 + 3 * SECONDS_PER_DAY,
+                party: Party::Seller
+            }
+        );
+    }
+
+    #[test]
+    fn test_settles_at_most_once_per_interval() {
+        let spec = call(1_500_000_000, 0, SOL);
+        let mut observations = vec![observation(0, 225_000_000, 150_000_000)];
+        // Hourly prices for two days, then expiry
+        observations.extend((1..48).map(|hour| Observation {
+            timestamp: AUG_1 + hour * 3_600,
+            asset_price_usd: 225_000_000 + hour as u64 * 100_000,
+            sol_price_usd: 150_000_000,
+        }));
+        observations.push(observation(30, 225_000_000, 150_000_000));
+        let report = backtest(&spec, &observations, &BacktestConfig::default()).unwrap();
+        assert_eq!(report.days.len(), 1);
+        assert_eq!(report.days[0].timestamp, AUG_1 + SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn test_errors() {
+        let observations = [observation(0, 225_000_000, 150_000_000), observation(5, 230_000_000, 150_000_000)];
+        let spec = call(1_500_000_000, 0, SOL);
+        assert!(matches!(
+            backtest(&spec, &observations, &BacktestConfig::default()),
+            Err(BacktestError::SeriesEndsBeforeExpiry { .. })
+        ));
+        assert_eq!(backtest(&spec, &[], &BacktestConfig::default()), Err(BacktestError::NoPurchase));
+
+        let put = OptionSpec { option_type: 2, ..spec };
+        assert_eq!(
+            backtest(&put, &observations, &BacktestConfig::default()),
+            Err(BacktestError::InvalidOptionType(2))
+        );
+
+        let zero_sol = [observation(0, 225_000_000, 150_000_000), observation(1, 225_000_000, 0)];
+        assert_eq!(
+            backtest(&spec, &zero_sol, &BacktestConfig::default()),
+            Err(BacktestError::Math(MathError::InvalidInput))
+        );
+    }
+}
@@ -0,0 +1,432 @@
+//! On-disk price cache
+//!
+//! Wraps any `PriceSource` with a JSON file keyed by symbol and date. A miss
+//! on a historical date pulls the provider's whole daily series in one request
+//! and stores every close, so a backfill costs one Alpha Vantage call per
+//! symbol instead of one per date. The range each series covered is recorded
+//! too, so dates up to its last close that it did not include (weekends,
+//! holidays, days older than the provider serves) are answered as missing
+//! without asking the provider again.
+
+use crate::source::parse_date;
+use crate::{Price, PriceOracleError, PriceSource};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60); // Matches the program's MAX_PRICE_AGE
+
+/// Where the cache lives and how long intraday quotes stay fresh
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+    pub path: PathBuf,  // JSON file, created on first write
+    pub ttl: Duration,  // Lifetime of spot quotes and of today's (still moving) daily bar
+    pub offline: bool,  // Serve only from cache, ignoring ttl; misses are CacheMiss errors
+}
+
+impl CacheConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        CacheConfig {
+            path: path.into(),
+            ttl: DEFAULT_TTL,
+            offline: false,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedPrice {
+    price_usd: u64,
+    timestamp: i64,  // Quote time (start of day for daily closes)
+    fetched_at: i64, // When the quote was written to the cache
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct DateRange {
+    first: String, // YYYY-MM-DD, inclusive
+    last: String,  // YYYY-MM-DD, inclusive
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    #[serde(default)]
+    daily: BTreeMap<String, BTreeMap<String, CachedPrice>>, // symbol -> YYYY-MM-DD -> close
+    #[serde(default)]
+    spot: BTreeMap<String, CachedPrice>, // symbol -> latest quote
+    #[serde(default)]
+    coverage: BTreeMap<String, DateRange>, // symbol -> dates spanned by the bulk series fetched
+    #[serde(default)]
+    missing: BTreeMap<String, BTreeSet<String>>, // symbol -> past dates a per-date source had no close for
+}
+
+impl CacheFile {
+    // Helper function to tell whether the provider is already known to have no close for a date
+    // A bulk series is the provider's full history up to its last close, so anything older
+    // that it left out (including dates before its first close) will not appear later
+    fn is_known_missing(&self, key: &str, date: &str) -> bool {
+        let covered = self.coverage.get(key).is_some_and(|range| date <= range.last.as_str());
+        covered || self.missing.get(key).is_some_and(|dates| dates.contains(date))
+    }
+
+    // Helper function to record the dates a bulk series spanned
+    // Overlapping ranges merge; a disjoint range replaces the older one
+    fn cover(&mut self, key: String, range: DateRange) {
+        let merged = match self.coverage.get(&key) {
+            Some(known) if known.first <= range.last && range.first <= known.last => DateRange {
+                first: known.first.clone().min(range.first),
+                last: known.last.clone().max(range.last),
+            },
+            _ => range,
+        };
+        self.coverage.insert(key, merged);
+    }
+}
+
+/// A `PriceSource` that reads through a JSON file cache
+pub struct CachedPriceSource<S: PriceSource> {
+    source: S,
+    config: CacheConfig,
+    file: Mutex<CacheFile>,
+}
+
+impl<S: PriceSource> CachedPriceSource<S> {
+    /// Load the cache file if it exists; a missing file starts an empty cache
+    pub fn open(source: S, config: CacheConfig) -> Result<Self, PriceOracleError> {
+        let file = match std::fs::read_to_string(&config.path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|error| PriceOracleError::Cache(std::io::Error::new(std::io::ErrorKind::InvalidData, error)))?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => CacheFile::default(),
+            Err(error) => return Err(PriceOracleError::Cache(error)),
+        };
+        Ok(CachedPriceSource {
+            source,
+            config,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.config.path
+    }
+
+    // Helper function to decide whether a cached quote may be served
+    // Closes before today never change; spot quotes and today's bar expire after ttl
+    fn is_fresh(&self, cached: &CachedPrice, intraday: bool, now: i64) -> bool {
+        self.config.offline || !intraday || now.saturating_sub(cached.fetched_at) <= self.config.ttl.as_secs() as i64
+    }
+
+    // Helper function to write the cache atomically (temp file, then rename)
+    fn persist(&self, file: &CacheFile) -> Result<(), PriceOracleError> {
+        let contents = serde_json::to_string_pretty(file)
+            .map_err(|error| PriceOracleError::Cache(std::io::Error::other(error)))?;
+        if let Some(parent) = self.config.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(PriceOracleError::Cache)?;
+        }
+        let temp = self.config.path.with_extension("tmp");
+        std::fs::write(&temp, contents).map_err(PriceOracleError::Cache)?;
+        std::fs::rename(&temp, &self.config.path).map_err(PriceOracleError::Cache)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheFile> {
+        self.file.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<S: PriceSource> PriceSource for CachedPriceSource<S> {
+    fn spot(&self, symbol: &str) -> Result<Price, PriceOracleError> {
+        let key = symbol.to_uppercase();
+        let now = Utc::now().timestamp();
+        if let Some(cached) = self.lock().spot.get(&key) {
+            if self.is_fresh(cached, true, now) {
+                return Ok(to_price(symbol, cached));
+            }
+        }
+        if self.config.offline {
+            return Err(PriceOracleError::CacheMiss {
+                symbol: symbol.to_string(),
+                date: None,
+            });
+        }
+
+        let price = self.source.spot(symbol)?;
+        let mut file = self.lock();
+        file.spot.insert(key, from_price(&price, now));
+        self.persist(&file)?;
+        Ok(price)
+    }
+
+    fn historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError> {
+        parse_date(date)?;
+        let key = symbol.to_uppercase();
+        let now = Utc::now().timestamp();
+        let intraday = date >= Utc::now().date_naive().format("%Y-%m-%d").to_string().as_str();
+        let known_missing = {
+            let file = self.lock();
+            match file.daily.get(&key).and_then(|days| days.get(date)) {
+                Some(cached) if self.is_fresh(cached, intraday, now) => return Ok(to_price(symbol, cached)),
+                Some(_) => false,
+                None => file.is_known_missing(&key, date),
+            }
+        };
+        if known_missing {
+            return Err(missing_date(symbol, date));
+        }
+        if self.config.offline {
+            return Err(PriceOracleError::CacheMiss {
+                symbol: symbol.to_string(),
+                date: Some(date.to_string()),
+            });
+        }
+
+        // One bulk request fills every date the provider returns
+        let series = self.source.daily_series(symbol)?;
+        let price = if series.is_empty() {
+            let price = match self.source.historical(symbol, date) {
+                // Past dates stay missing; today's close may still arrive
+                Err(PriceOracleError::MissingDate { .. }) if !intraday => {
+                    let mut file = self.lock();
+                    file.missing.entry(key).or_default().insert(date.to_string());
+                    self.persist(&file)?;
+                    return Err(missing_date(symbol, date));
+                }
+                result => result?,
+            };
+            let mut file = self.lock();
+            file.daily.entry(key).or_default().insert(date.to_string(), from_price(&price, now));
+            self.persist(&file)?;
+            price
+        } else {
+            let mut file = self.lock();
+            let days = file.daily.entry(key.clone()).or_default();
+            for price in &series {
+                days.insert(date_of(price), from_price(price, now));
+            }
+            let found = days.get(date).map(|cached| to_price(symbol, cached));
+            let dates = series.iter().map(date_of);
+            if let (Some(first), Some(last)) = (dates.clone().min(), dates.max()) {
+                file.cover(key, DateRange { first, last });
+            }
+            self.persist(&file)?;
+            found.ok_or_else(|| missing_date(symbol, date))?
+        };
+        Ok(price)
+    }
+
+    fn daily_series(&self, symbol: &str) -> Result<Vec<Price>, PriceOracleError> {
+        let key = symbol.to_uppercase();
+        let file = self.lock();
+        Ok(file
+            .daily
+            .get(&key)
+            .map(|days| days.values().map(|cached| to_price(symbol, cached)).collect())
+            .unwrap_or_default())
+    }
+}
+
+fn to_price(symbol: &str, cached: &CachedPrice) -> Price {
+    Price {
+        symbol: symbol.to_string(),
+        price_usd: cached.price_usd,
+        timestamp: cached.timestamp,
+    }
+}
+
+fn from_price(price: &Price, fetched_at: i64) -> CachedPrice {
+    CachedPrice {
+        price_usd: price.price_usd,
+        timestamp: price.timestamp,
+        fetched_at,
+    }
+}
+
+fn missing_date(symbol: &str, date: &str) -> PriceOracleError {
+    PriceOracleError::MissingDate {
+        symbol: symbol.to_string(),
+        date: date.to_string(),
+    }
+}
+
+fn date_of(price: &Price) -> String {
+    chrono::DateTime::from_timestamp(price.timestamp, 0)
+        .map(|time| time.date_naive().format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixturePriceSource;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Counts how often the wrapped source is hit
+    #[derive(Default)]
+    struct CountingSource {
+        spot_calls: AtomicUsize,
+        historical_calls: AtomicUsize,
+        series_calls: AtomicUsize,
+        per_date: bool, // Behave like a source without a bulk endpoint
+    }
+
+    impl PriceSource for CountingSource {
+        fn spot(&self, symbol: &str) -> Result<Price, PriceOracleError> {
+            self.spot_calls.fetch_add(1, Ordering::SeqCst);
+            FixturePriceSource::sample().spot(symbol)
+        }
+
+        fn historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError> {
+            self.historical_calls.fetch_add(1, Ordering::SeqCst);
+            FixturePriceSource::sample().historical(symbol, date)
+        }
+
+        fn daily_series(&self, symbol: &str) -> Result<Vec<Price>, PriceOracleError> {
+            self.series_calls.fetch_add(1, Ordering::SeqCst);
+            if self.per_date {
+                return Ok(Vec::new());
+            }
+            FixturePriceSource::sample().daily_series(symbol)
+        }
+    }
+
+    fn cache_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("price_oracle_{}_{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_backfill_uses_one_bulk_request() {
+        let path = cache_path("backfill");
+        let cache = CachedPriceSource::open(CountingSource::default(), CacheConfig::new(&path)).unwrap();
+
+        assert_eq!(cache.historical("AAPL", "2025-08-01").unwrap().price_usd, 225_500_000);
+        assert_eq!(cache.historical("AAPL", "2025-08-30").unwrap().price_usd, 238_750_000);
+        assert_eq!(cache.historical("aapl", "2025-09-01").unwrap().price_usd, 240_000_000);
+        assert_eq!(cache.source.series_calls.load(Ordering::SeqCst), 1);
+
+        // A date inside the series without a close is a miss answered from the recorded coverage
+        for _ in 0..3 {
+            assert!(matches!(
+                cache.historical("AAPL", "2025-08-02"),
+                Err(PriceOracleError::MissingDate { .. })
+            ));
+        }
+        assert_eq!(cache.source.series_calls.load(Ordering::SeqCst), 1);
+
+        // So is a date older than anything the series returned
+        for _ in 0..3 {
+            assert!(matches!(
+                cache.historical("AAPL", "2025-07-01"),
+                Err(PriceOracleError::MissingDate { .. })
+            ));
+        }
+        assert_eq!(cache.source.series_calls.load(Ordering::SeqCst), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_dates_before_the_series_cost_one_request() {
+        let path = cache_path("before_series");
+        let cache = CachedPriceSource::open(CountingSource::default(), CacheConfig::new(&path)).unwrap();
+
+        for _ in 0..3 {
+            assert!(matches!(
+                cache.historical("AAPL", "2024-01-02"),
+                Err(PriceOracleError::MissingDate { .. })
+            ));
+        }
+        assert_eq!(cache.source.series_calls.load(Ordering::SeqCst), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_missing_dates_are_remembered_for_per_date_sources() {
+        let path = cache_path("missing");
+        let source = CountingSource {
+            per_date: true,
+            ..CountingSource::default()
+        };
+        let cache = CachedPriceSource::open(source, CacheConfig::new(&path)).unwrap();
+
+        for _ in 0..3 {
+            assert!(matches!(
+                cache.historical("AAPL", "2025-08-02"),
+                Err(PriceOracleError::MissingDate { .. })
+            ));
+        }
+        assert_eq!(cache.source.historical_calls.load(Ordering::SeqCst), 1);
+
+        // The negative entry survives a reopen
+        let reopened = CachedPriceSource::open(CountingSource::default(), CacheConfig::new(&path).offline()).unwrap();
+        assert!(matches!(
+            reopened.historical("AAPL", "2025-08-02"),
+            Err(PriceOracleError::MissingDate { .. })
+        ));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_offline_serves_persisted_cache_only() {
+        let path = cache_path("offline");
+        {
+            let cache = CachedPriceSource::open(CountingSource::default(), CacheConfig::new(&path)).unwrap();
+            cache.historical("SOL", "2025-08-15").unwrap();
+            cache.spot("SOL").unwrap();
+        }
+
+        let offline = CachedPriceSource::open(CountingSource::default(), CacheConfig::new(&path).offline()).unwrap();
+        assert_eq!(offline.historical("SOL", "2025-09-01").unwrap().price_usd, 165_000_000);
+        assert_eq!(offline.spot("SOL").unwrap().price_usd, 165_000_000);
+        assert!(matches!(
+            offline.historical("AAPL", "2025-08-01"),
+            Err(PriceOracleError::CacheMiss { date: Some(_), .. })
+        ));
+        assert_eq!(offline.source.series_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(offline.source.spot_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(offline.daily_series("SOL").unwrap().len(), 8);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_spot_ttl() {
+        let path = cache_path("ttl");
+        let cache = CachedPriceSource::open(CountingSource::default(), CacheConfig::new(&path)).unwrap();
+        cache.spot("AAPL").unwrap();
+        cache.spot("AAPL").unwrap();
+        assert_eq!(cache.source.spot_calls.load(Ordering::SeqCst), 1);
+
+        let expired = CachedPriceSource::open(
+            CountingSource::default(),
+            CacheConfig::new(&path).with_ttl(Duration::ZERO),
+        )
+        .unwrap();
+        // Backdate the cached quote past a zero ttl
+        expired.lock().spot.get_mut("AAPL").unwrap().fetched_at -= 1;
+        expired.spot("AAPL").unwrap();
+        assert_eq!(expired.source.spot_calls.load(Ordering::SeqCst), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_corrupt_cache_file() {
+        let path = cache_path("corrupt");
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            CachedPriceSource::open(FixturePriceSource::new(), CacheConfig::new(&path)),
+            Err(PriceOracleError::Cache(_))
+        ));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,186 @@
+//! Exact decimal parsing into the 6 decimal u64 price representation
+//!
+//! Provider prices arrive as decimal strings (or JSON numbers rendered back to
+//! their shortest decimal form) and are scaled digit by digit, so 2.01 becomes
+//! 2_010_000 rather than the 2_009_999 a float multiplication gives.
+
+use std::fmt;
+
+/// How digits beyond the target decimals are resolved, shared with `option_math::ratio`
+pub use option_math::Rounding;
+
+/// Decimal places of the u64 price representation
+pub const PRICE_DECIMALS: u32 = 6;
+
+/// Why a decimal string could not be converted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecimalError {
+    /// Empty, non-numeric, NaN or infinite input
+    Invalid,
+    /// Prices cannot be negative
+    Negative,
+    /// Scaled value does not fit in u64
+    Overflow,
+}
+
+impl fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecimalError::Invalid => write!(f, "not a finite decimal number"),
+            DecimalError::Negative => write!(f, "negative value"),
+            DecimalError::Overflow => write!(f, "value exceeds u64 at 6 decimals"),
+        }
+    }
+}
+
+impl std::error::Error for DecimalError {}
+
+/// Parse a decimal string such as "228.7500", "+0.29" or "1.5e-7" into USD * 1_000_000
+pub fn parse_price(input: &str, rounding: Rounding) -> Result<u64, DecimalError> {
+    parse_scaled(input, PRICE_DECIMALS, rounding)
+}
+
+/// Parse a decimal string into an integer scaled by 10^decimals
+pub fn parse_scaled(input: &str, decimals: u32, rounding: Rounding) -> Result<u64, DecimalError> {
+    let input = input.trim();
+    let (unsigned, negative) = match input.as_bytes().first() {
+        Some(b'-') => (&input[1..], true),
+        Some(b'+') => (&input[1..], false),
+        _ => (input, false),
+    };
+
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(index) => {
+            let exponent: i32 = unsigned[index + 1..].parse().map_err(|_| DecimalError::Invalid)?;
+            (&unsigned[..index], exponent)
+        }
+        None => (unsigned, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return Err(DecimalError::Invalid);
+    }
+    if !integer.bytes().chain(fraction.bytes()).all(|byte| byte.is_ascii_digit()) {
+        return Err(DecimalError::Invalid);
+    }
+
+    let digits: Vec<u8> = integer.bytes().chain(fraction.bytes()).map(|byte| byte - b'0').collect();
+    if negative {
+        // "-0.000" is still zero; anything else below zero is rejected
+        return if digits.iter().all(|&digit| digit == 0) { Ok(0) } else { Err(DecimalError::Negative) };
+    }
+
+    // Digits [0, split) form the scaled integer, the rest are discarded by rounding
+    let split = integer.len() as i64 + exponent as i64 + decimals as i64;
+    let kept_len = split.clamp(0, digits.len() as i64) as usize;
+    let (kept, discarded) = digits.split_at(kept_len);
+
+    let mut value: u64 = 0;
+    for &digit in kept {
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(digit as u64))
+            .ok_or(DecimalError::Overflow)?;
+    }
+    // Trailing zeros implied by a positive exponent; zero stays zero however large
+    if value != 0 {
+        for _ in 0..(split - kept_len as i64).max(0) {
+            value = value.checked_mul(10).ok_or(DecimalError::Overflow)?;
+        }
+    }
+
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => discarded.iter().any(|&digit| digit != 0),
+        Rounding::HalfUp => round_half(discarded, split) >= Half::Exact,
+        Rounding::HalfEven => match round_half(discarded, split) {
+            Half::Above => true,
+            Half::Exact => value % 2 == 1,
+            Half::Below => false,
+        },
+    };
+    if round_up {
+        value = value.checked_add(1).ok_or(DecimalError::Overflow)?;
+    }
+    Ok(value)
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Half {
+    Below,
+    Exact,
+    Above,
+}
+
+// Helper function to compare the discarded digits against one half of the last kept unit
+// A negative split means leading zeros were discarded too, so the remainder is below half
+fn round_half(discarded: &[u8], split: i64) -> Half {
+    if split < 0 {
+        return Half::Below;
+    }
+    match discarded.split_first() {
+        Some((&first, _)) if first > 5 => Half::Above,
+        Some((&5, rest)) if rest.iter().any(|&digit| digit != 0) => Half::Above,
+        Some((&5, _)) => Half::Exact,
+        _ => Half::Below,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_bad_float_cases() {
+        // (x * 1e6) as u64 truncates each of these one unit low
+        for (input, expected) in [("2.01", 2_010_000), ("4.02", 4_020_000), ("8.03", 8_030_000), ("1.005", 1_005_000)] {
+            assert_eq!(parse_price(input, Rounding::Down), Ok(expected), "{}", input);
+            assert_eq!((input.parse::<f64>().unwrap() * 1_000_000.0) as u64, expected - 1, "{}", input);
+        }
+
+        // Beyond 2^53 floats lose the last digits
+        assert_eq!(parse_price("18446744073709.551615", Rounding::Down), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_rejects_negative_nan_and_overflow() {
+        assert_eq!(parse_price("-1.5", Rounding::HalfEven), Err(DecimalError::Negative));
+        assert_eq!(parse_price("-0.00", Rounding::HalfEven), Ok(0));
+        for input in ["NaN", "inf", "-inf", "", ".", "1.2.3", "12a", "1e", "0x10", " - 1"] {
+            assert_eq!(parse_price(input, Rounding::HalfEven), Err(DecimalError::Invalid), "{:?}", input);
+        }
+        assert_eq!(parse_price("18446744073709.551616", Rounding::Down), Err(DecimalError::Overflow));
+        assert_eq!(parse_price("18446744073709.5516155", Rounding::HalfUp), Err(DecimalError::Overflow));
+        assert_eq!(parse_price("1e20", Rounding::Down), Err(DecimalError::Overflow));
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let cases = [
+            // input, Down, Up, HalfUp, HalfEven
+            ("1.0000005", 1_000_000, 1_000_001, 1_000_001, 1_000_000),
+            ("1.0000015", 1_000_001, 1_000_002, 1_000_002, 1_000_002),
+            ("1.00000050001", 1_000_000, 1_000_001, 1_000_001, 1_000_001),
+            ("1.0000004999", 1_000_000, 1_000_001, 1_000_000, 1_000_000),
+            ("0.0000000001", 0, 1, 0, 0),
+        ];
+        for (input, down, up, half_up, half_even) in cases {
+            assert_eq!(parse_price(input, Rounding::Down), Ok(down), "{} down", input);
+            assert_eq!(parse_price(input, Rounding::Up), Ok(up), "{} up", input);
+            assert_eq!(parse_price(input, Rounding::HalfUp), Ok(half_up), "{} half up", input);
+            assert_eq!(parse_price(input, Rounding::HalfEven), Ok(half_even), "{} half even", input);
+        }
+    }
+
+    #[test]
+    fn test_exponents_and_signs() {
+        assert_eq!(parse_price("1.5e-7", Rounding::HalfUp), Ok(0));
+        assert_eq!(parse_price("5e-7", Rounding::HalfUp), Ok(1));
+        assert_eq!(parse_price("2.2875E2", Rounding::Down), Ok(228_750_000));
+        assert_eq!(parse_price("+150", Rounding::Down), Ok(150_000_000));
+        assert_eq!(parse_price(" 165.0 ", Rounding::Down), Ok(165_000_000));
+        assert_eq!(parse_price(".5", Rounding::Down), Ok(500_000));
+        assert_eq!(parse_price("7.", Rounding::Down), Ok(7_000_000));
+        assert_eq!(parse_scaled("1.25", 9, Rounding::Down), Ok(1_250_000_000));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::decimal::DecimalError;
+use std::fmt;
+
+/// Errors returned by the price fetchers, split by how a keeper should react
+#[derive(Debug)]
+pub enum PriceOracleError {
+    /// Request failed to connect, timed out or returned a non-success status (retry)
+    Network(reqwest::Error),
+    /// Provider throttled the request, e.g. Alpha Vantage "Note"/"Information" payloads (back off)
+    RateLimited(String),
+    /// Provider has no data for the symbol (alert)
+    MissingSymbol(String),
+    /// Provider has no data for the symbol on the requested date
+    MissingDate { symbol: String, date: String },
+    /// Date is not in YYYY-MM-DD format
+    InvalidDate(String),
+    /// Provider quoted a price that is not a finite, non-negative decimal fitting in u64
+    InvalidPrice { value: String, error: DecimalError },
+    /// Too few sources returned fresh, agreeing quotes to aggregate; `errors` names each source that failed
+    InsufficientSources {
+        symbol: String,
+        required: usize,
+        available: usize,
+        errors: Vec<(String, PriceOracleError)>,
+    },
+    /// Offline cache has no entry for the symbol (and date, for historical lookups)
+    CacheMiss { symbol: String, date: Option<String> },
+    /// Cache file could not be read, parsed or written
+    Cache(std::io::Error),
+    /// Response body was not the JSON shape expected from the provider
+    MalformedResponse(String),
+    /// Fixture file could not be read or a row could not be parsed
+    Fixture(String),
+}
+
+impl fmt::Display for PriceOracleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceOracleError::Network(error) => write!(f, "network error: {}", error),
+            PriceOracleError::RateLimited(message) => write!(f, "rate limited: {}", message),
+            PriceOracleError::MissingSymbol(symbol) => write!(f, "no price data for symbol {}", symbol),
+            PriceOracleError::MissingDate { symbol, date } => {
+                write!(f, "no price data for {} on {}", symbol, date)
+            }
+            PriceOracleError::InvalidDate(date) => write!(f, "invalid date {}, expected YYYY-MM-DD", date),
+            PriceOracleError::InvalidPrice { value, error } => write!(f, "invalid price {:?}: {}", value, error),
+            PriceOracleError::InsufficientSources { symbol, required, available, errors } => {
+                write!(f, "{} of {} required sources agreed on {}", available, required, symbol)?;
+                errors.iter().try_for_each(|(source, error)| write!(f, "; {}: {}", source, error))
+            }
+            PriceOracleError::CacheMiss { symbol, date: Some(date) } => {
+                write!(f, "no cached price for {} on {}", symbol, date)
+            }
+            PriceOracleError::CacheMiss { symbol, date: None } => write!(f, "no cached spot price for {}", symbol),
+            PriceOracleError::Cache(error) => write!(f, "price cache error: {}", error),
+            PriceOracleError::MalformedResponse(message) => write!(f, "malformed response: {}", message),
+            PriceOracleError::Fixture(message) => write!(f, "fixture error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PriceOracleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PriceOracleError::Network(error) => Some(error),
+            PriceOracleError::Cache(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for PriceOracleError {
+    fn from(error: reqwest::Error) -> Self {
+        PriceOracleError::Network(error)
+    }
+}
+
+impl From<serde_json::Error> for PriceOracleError {
+    fn from(error: serde_json::Error) -> Self {
+        PriceOracleError::MalformedResponse(error.to_string())
+    }
+}
@@ -0,0 +1,323 @@
+//! File-driven price fixtures for tests and backtests
+//!
+//! Loads daily OHLC bars for any symbol from CSV (`date,open,high,low,close`,
+//! extra columns ignored) or JSON (an array of objects with the same keys).
+//! Dates missing from a fixture are errors unless an interpolation policy is
+//! chosen explicitly, so a backtest never silently prices off a default.
+
+use crate::decimal::Rounding;
+use crate::pricing::ratio::div_round;
+use crate::source::{parse_date, parse_price_str, start_of_day};
+use crate::{AsyncPriceSource, Price, PriceOracleError, PriceSource};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const FIXTURE_ROUNDING: Rounding = Rounding::HalfEven;
+
+/// One day of prices in USD with 6 decimals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bar {
+    pub open: u64,
+    pub high: u64,
+    pub low: u64,
+    pub close: u64,
+}
+
+/// How a date between two fixture bars is priced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Only dates present in the fixture resolve; others are MissingDate
+    #[default]
+    None,
+    /// Carry the most recent earlier close forward, within the fixture's range
+    PreviousClose,
+    /// Straight line between the surrounding closes, by calendar day
+    Linear,
+}
+
+/// A `PriceSource` serving daily closes from fixture files
+#[derive(Debug, Clone, Default)]
+pub struct FixturePriceSource {
+    series: BTreeMap<String, BTreeMap<NaiveDate, Bar>>,
+    interpolation: Interpolation,
+}
+
+impl FixturePriceSource {
+    pub fn new() -> Self {
+        FixturePriceSource::default()
+    }
+
+    /// AAPL and SOL for August-September 2025, bundled with the crate
+    pub fn sample() -> Self {
+        FixturePriceSource::new()
+            .with_csv("AAPL", include_str!("../fixtures/AAPL.csv"))
+            .and_then(|source| source.with_csv("SOL", include_str!("../fixtures/SOL.csv")))
+            .expect("bundled fixtures are valid")
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Add bars for a symbol, replacing any existing bars on the same dates
+    pub fn with_bars(mut self, symbol: &str, bars: impl IntoIterator<Item = (NaiveDate, Bar)>) -> Self {
+        self.series.entry(symbol.to_uppercase()).or_default().extend(bars);
+        self
+    }
+
+    /// Add bars from CSV text
+    pub fn with_csv(self, symbol: &str, csv: &str) -> Result<Self, PriceOracleError> {
+        let bars = parse_csv(csv)?;
+        Ok(self.with_bars(symbol, bars))
+    }
+
+    /// Add bars from JSON text
+    pub fn with_json(self, symbol: &str, json: &str) -> Result<Self, PriceOracleError> {
+        let bars = parse_json(json)?;
+        Ok(self.with_bars(symbol, bars))
+    }
+
+    /// Add bars from a .csv or .json file
+    pub fn with_file(self, symbol: &str, path: impl AsRef<Path>) -> Result<Self, PriceOracleError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| PriceOracleError::Fixture(format!("{}: {}", path.display(), error)))?;
+        let with_context = |error: PriceOracleError| PriceOracleError::Fixture(format!("{}: {}", path.display(), error));
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => self.with_csv(symbol, &contents).map_err(with_context),
+            Some("json") => self.with_json(symbol, &contents).map_err(with_context),
+            _ => Err(PriceOracleError::Fixture(format!("{}: expected a .csv or .json file", path.display()))),
+        }
+    }
+
+    /// Load every SYMBOL.csv and SYMBOL.json in a directory, named by file stem
+    pub fn from_dir(path: impl AsRef<Path>) -> Result<Self, PriceOracleError> {
+        let path = path.as_ref();
+        let entries = std::fs::read_dir(path)
+            .map_err(|error| PriceOracleError::Fixture(format!("{}: {}", path.display(), error)))?;
+        let mut files: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| matches!(file.extension().and_then(|extension| extension.to_str()), Some("csv" | "json")))
+            .collect();
+        files.sort();
+
+        files.into_iter().try_fold(FixturePriceSource::new(), |source, file| {
+            let symbol = file.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default().to_string();
+            source.with_file(&symbol, &file)
+        })
+    }
+
+    /// Full bar for a date present in the fixture
+    pub fn bar(&self, symbol: &str, date: &str) -> Result<Bar, PriceOracleError> {
+        let day = parse_date(date)?;
+        self.bars(symbol)?.get(&day).copied().ok_or_else(|| missing_date(symbol, date))
+    }
+
+    fn bars(&self, symbol: &str) -> Result<&BTreeMap<NaiveDate, Bar>, PriceOracleError> {
+        self.series
+            .get(&symbol.to_uppercase())
+            .ok_or_else(|| PriceOracleError::MissingSymbol(symbol.to_string()))
+    }
+
+    // Helper function to resolve a close under the interpolation policy
+    fn close_on(&self, symbol: &str, date: &str) -> Result<u64, PriceOracleError> {
+        let day = parse_date(date)?;
+        let bars = self.bars(symbol)?;
+        if let Some(bar) = bars.get(&day) {
+            return Ok(bar.close);
+        }
+
+        let previous = bars.range(..day).next_back();
+        let next = bars.range(day..).next();
+        match (self.interpolation, previous, next) {
+            (Interpolation::PreviousClose, Some((_, bar)), Some(_)) => Ok(bar.close),
+            (Interpolation::Linear, Some((start, from)), Some((end, to))) => {
+                let span = (*end - *start).num_days() as u128;
+                let elapsed = (day - *start).num_days() as u128;
+                // from + (to - from) * elapsed / span, kept unsigned by splitting on direction
+                let close = if to.close >= from.close {
+                    from.close as u128 + div_round((to.close - from.close) as u128 * elapsed, span, FIXTURE_ROUNDING)
+                } else {
+                    from.close as u128 - div_round((from.close - to.close) as u128 * elapsed, span, FIXTURE_ROUNDING)
+                };
+                Ok(close as u64)
+            }
+            _ => Err(missing_date(symbol, date)),
+        }
+    }
+}
+
+impl PriceSource for FixturePriceSource {
+    /// Latest close in the fixture, stamped with its own date
+    fn spot(&self, symbol: &str) -> Result<Price, PriceOracleError> {
+        let (day, bar) = self
+            .bars(symbol)?
+            .iter()
+            .next_back()
+            .ok_or_else(|| PriceOracleError::MissingSymbol(symbol.to_string()))?;
+        Ok(price(symbol, bar.close, *day))
+    }
+
+    fn historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError> {
+        let close = self.close_on(symbol, date)?;
+        Ok(price(symbol, close, parse_date(date)?))
+    }
+
+    fn daily_series(&self, symbol: &str) -> Result<Vec<Price>, PriceOracleError> {
+        Ok(self.bars(symbol)?.iter().map(|(day, bar)| price(symbol, bar.close, *day)).collect())
+    }
+}
+
+impl AsyncPriceSource for FixturePriceSource {
+    async fn fetch_spot(&self, symbol: &str) -> Result<Price, PriceOracleError> {
+        self.spot(symbol)
+    }
+
+    async fn fetch_historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError> {
+        self.historical(symbol, date)
+    }
+}
+
+fn price(symbol: &str, price_usd: u64, day: NaiveDate) -> Price {
+    Price {
+        symbol: symbol.to_string(),
+        price_usd,
+        timestamp: start_of_day(day),
+    }
+}
+
+fn missing_date(symbol: &str, date: &str) -> PriceOracleError {
+    PriceOracleError::MissingDate {
+        symbol: symbol.to_string(),
+        date: date.to_string(),
+    }
+}
+
+fn parse_csv(csv: &str) -> Result<Vec<(NaiveDate, Bar)>, PriceOracleError> {
+    let mut lines = csv.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let (_, header) = lines.next().ok_or_else(|| PriceOracleError::Fixture("empty CSV".to_string()))?;
+    let columns: Vec<String> = header.split(',').map(|column| column.trim().to_lowercase()).collect();
+    let index = |name: &str| {
+        columns
+            .iter()
+            .position(|column| column == name)
+            .ok_or_else(|| PriceOracleError::Fixture(format!("CSV header is missing {}", name)))
+    };
+    let (date, open, high, low, close) = (index("date")?, index("open")?, index("high")?, index("low")?, index("close")?);
+
+    lines
+        .map(|(number, line)| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let field = |column: usize| {
+                fields
+                    .get(column)
+                    .copied()
+                    .ok_or_else(|| PriceOracleError::Fixture(format!("line {}: too few columns", number + 1)))
+            };
+            let at_line = |error: PriceOracleError| PriceOracleError::Fixture(format!("line {}: {}", number + 1, error));
+            let bar = Bar {
+                open: parse_price_str(field(open)?).map_err(at_line)?,
+                high: parse_price_str(field(high)?).map_err(at_line)?,
+                low: parse_price_str(field(low)?).map_err(at_line)?,
+                close: parse_price_str(field(close)?).map_err(at_line)?,
+            };
+            Ok((parse_date(field(date)?).map_err(at_line)?, bar))
+        })
+        .collect()
+}
+
+fn parse_json(json: &str) -> Result<Vec<(NaiveDate, Bar)>, PriceOracleError> {
+    let rows: Vec<serde_json::Value> =
+        serde_json::from_str(json).map_err(|error| PriceOracleError::Fixture(format!("invalid JSON: {}", error)))?;
+
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let at_row = |error: PriceOracleError| PriceOracleError::Fixture(format!("row {}: {}", index, error));
+            // Prices may be JSON strings ("225.50") or numbers (225.5)
+            let field = |name: &str| -> Result<String, PriceOracleError> {
+                match &row[name] {
+                    serde_json::Value::String(value) => Ok(value.clone()),
+                    serde_json::Value::Number(value) => Ok(value.to_string()),
+                    _ => Err(PriceOracleError::Fixture(format!("row {}: missing {}", index, name))),
+                }
+            };
+            let bar = Bar {
+                open: parse_price_str(&field("open")?).map_err(at_row)?,
+                high: parse_price_str(&field("high")?).map_err(at_row)?,
+                low: parse_price_str(&field("low")?).map_err(at_row)?,
+                close: parse_price_str(&field("close")?).map_err(at_row)?,
+            };
+            Ok((parse_date(&field("date")?).map_err(at_row)?, bar))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_fixtures() {
+        let source = FixturePriceSource::sample();
+        assert_eq!(source.historical("AAPL", "2025-08-01").unwrap().price_usd, 225_500_000);
+        assert_eq!(source.historical("sol", "2025-09-01").unwrap().price_usd, 165_000_000);
+        assert_eq!(source.spot("AAPL").unwrap().timestamp, 1_756_684_800); // 2025-09-01
+        assert_eq!(
+            source.bar("SOL", "2025-08-10").unwrap(),
+            Bar { open: 152_100_000, high: 152_600_000, low: 146_300_000, close: 148_000_000 }
+        );
+        assert_eq!(source.daily_series("SOL").unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_unknown_dates_and_symbols_error_by_default() {
+        let source = FixturePriceSource::sample();
+        assert!(matches!(source.historical("AAPL", "2025-08-02"), Err(PriceOracleError::MissingDate { .. })));
+        assert!(matches!(source.historical("TSLA", "2025-08-01"), Err(PriceOracleError::MissingSymbol(_))));
+        assert!(matches!(source.historical("AAPL", "2025/08/01"), Err(PriceOracleError::InvalidDate(_))));
+    }
+
+    #[test]
+    fn test_interpolation_policies() {
+        let previous = FixturePriceSource::sample().with_interpolation(Interpolation::PreviousClose);
+        assert_eq!(previous.historical("AAPL", "2025-08-04").unwrap().price_usd, 225_500_000);
+        assert!(matches!(previous.historical("AAPL", "2025-12-31"), Err(PriceOracleError::MissingDate { .. })));
+        assert!(matches!(previous.historical("AAPL", "2025-07-31"), Err(PriceOracleError::MissingDate { .. })));
+
+        let linear = FixturePriceSource::sample().with_interpolation(Interpolation::Linear);
+        // 225.50 -> 228.75 over 4 days: 226.3125 after one
+        assert_eq!(linear.historical("AAPL", "2025-08-02").unwrap().price_usd, 226_312_500);
+        // 228.75 -> 223.25 over 5 days: 226.55 after two
+        assert_eq!(linear.historical("AAPL", "2025-08-07").unwrap().price_usd, 226_550_000);
+        assert!(matches!(linear.historical("AAPL", "2025-09-02"), Err(PriceOracleError::MissingDate { .. })));
+    }
+
+    #[test]
+    fn test_json_fixture_and_bad_rows() {
+        let json = r#"[
+            { "date": "2025-08-01", "open": "1.00", "high": 1.2, "low": "0.95", "close": "1.10" },
+            { "date": "2025-08-02", "open": 1.1, "high": 1.3, "low": 1.05, "close": 1.25 }
+        ]"#;
+        let source = FixturePriceSource::new().with_json("JUP", json).unwrap();
+        assert_eq!(source.historical("JUP", "2025-08-02").unwrap().price_usd, 1_250_000);
+
+        let negative = "date,open,high,low,close\n2025-08-01,1,1,1,-1\n";
+        assert!(matches!(
+            FixturePriceSource::new().with_csv("JUP", negative),
+            Err(PriceOracleError::Fixture(message)) if message.starts_with("line 2")
+        ));
+        let missing_column = "date,open,high,close\n2025-08-01,1,1,1\n";
+        assert!(matches!(FixturePriceSource::new().with_csv("JUP", missing_column), Err(PriceOracleError::Fixture(_))));
+    }
+
+    #[test]
+    fn test_loads_fixture_directory() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+        let source = FixturePriceSource::from_dir(dir).unwrap();
+        assert_eq!(source.historical("AAPL", "2025-08-30").unwrap().price_usd, 238_750_000);
+        assert_eq!(source.historical("SOL", "2025-08-30").unwrap().price_usd, 162_000_000);
+    }
+}
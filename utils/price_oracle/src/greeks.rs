@@ -0,0 +1,175 @@
+//! Black-Scholes greeks for option contracts held by the risk desk
+//!
+//! Inputs and outputs use the program's fixed-point conventions: ratios and
+//! strikes are lamports per asset unit (1e9 = 1 SOL), and volatility, rate
+//! and the greeks themselves are scaled by 1e9.
+
+use option_math::settlement::scale_by_notional;
+use option_math::{d1_d2, div, exp, mul, norm_cdf, norm_pdf, sqrt, year_fraction, MathError, OptionKind, SCALE};
+
+const DAYS_PER_YEAR: i128 = 365;
+
+/// The contract fields the greeks depend on, as stored in `OptionContract`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GreeksInput {
+    pub option_type: u8,    // 0: Call, 1: Put
+    pub strike: u64,        // Strike (asset/SOL ratio in lamports)
+    pub expiry_date: i64,   // Expiry timestamp
+    pub current_ratio: u64, // Current asset/SOL ratio in lamports
+    pub now: i64,           // Valuation timestamp
+    pub contract_size: u64, // Units of the underlying per contract
+    pub quantity: u64,      // Number of contracts
+}
+
+/// Sensitivities of the whole position (contract_size * quantity asset units), scaled by 1e9
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Greeks {
+    pub delta: i64, // Change in premium per unit change in the ratio (1e9 = 1.0)
+    pub gamma: i64, // Change in delta per 1 SOL change in the ratio
+    pub vega: i64,  // Lamports per 1.0 (100 percentage points) change in volatility
+    pub theta: i64, // Lamports per day of time decay (negative for long options)
+    pub rho: i64,   // Lamports per 1.0 (100 percentage points) change in rate
+}
+
+/// Calculate delta, gamma, vega, theta and rho for a contract
+/// volatility: Annualized volatility (1e9 = 100%)
+/// rate: Annualized risk-free rate (1e9 = 100%)
+/// Expired contracts and zero volatility have no defined greeks and return InvalidInput
+pub fn calculate_greeks(input: &GreeksInput, volatility: u64, rate: i64) -> Result<Greeks, MathError> {
+    let kind = OptionKind::from_u8(input.option_type).ok_or(MathError::InvalidInput)?;
+    let time_to_expiry = input.expiry_date.checked_sub(input.now).ok_or(MathError::Overflow)?;
+    if time_to_expiry <= 0
+        || volatility == 0
+        || input.current_ratio == 0
+        || input.strike == 0
+        || input.contract_size == 0
+        || input.quantity == 0
+    {
+        return Err(MathError::InvalidInput);
+    }
+
+    let spot = input.current_ratio as i128;
+    let strike = input.strike as i128;
+    let volatility = volatility as i128;
+    let rate = rate as i128;
+
+    let t = year_fraction(time_to_expiry)?;
+    let sqrt_t = sqrt(t)?;
+    let discounted_strike = mul(strike, exp(-mul(rate, t)?)?)?;
+    let (d1, d2) = d1_d2(spot, strike, t, volatility, rate)?;
+    let pdf_d1 = norm_pdf(d1)?;
+
+    // Terms shared by calls and puts
+    let gamma = div(pdf_d1, mul(spot, mul(volatility, sqrt_t)?)?)?;
+    let vega = mul(mul(spot, pdf_d1)?, sqrt_t)?;
+    let decay = -div(mul(mul(spot, pdf_d1)?, volatility)?, 2 * sqrt_t)?;
+
+    let (delta, theta_per_year, rho) = match kind {
+        OptionKind::Call => {
+            let n_d2 = norm_cdf(d2)?;
+            (
+                norm_cdf(d1)?,
+                decay - mul(mul(rate, discounted_strike)?, n_d2)?,
+                mul(mul(t, discounted_strike)?, n_d2)?,
+            )
+        }
+        OptionKind::Put => {
+            let n_minus_d2 = norm_cdf(-d2)?;
+            (
+                norm_cdf(d1)? - SCALE,
+                decay + mul(mul(rate, discounted_strike)?, n_minus_d2)?,
+                -mul(mul(t, discounted_strike)?, n_minus_d2)?,
+            )
+        }
+    };
+
+    let notional = |per_unit: i128| to_position(per_unit, input.contract_size, input.quantity);
+    Ok(Greeks {
+        delta: notional(delta)?,
+        gamma: notional(gamma)?,
+        vega: notional(vega)?,
+        theta: notional(theta_per_year / DAYS_PER_YEAR)?,
+        rho: notional(rho)?,
+    })
+}
+
+// Helper function to scale a signed per-unit greek by the contract's notional
+fn to_position(per_unit: i128, contract_size: u64, quantity: u64) -> Result<i64, MathError> {
+    let magnitude = u64::try_from(per_unit.unsigned_abs()).map_err(|_| MathError::Overflow)?;
+    let scaled = i64::try_from(scale_by_notional(magnitude, contract_size, quantity)?).map_err(|_| MathError::Overflow)?;
+    Ok(if per_unit < 0 { -scaled } else { scaled })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const F: f64 = SCALE as f64;
+    const ONE_YEAR: i64 = 365 * 24 * 60 * 60;
+
+    // S = 100, K = 100, T = 1y, sigma = 20%, r = 5%
+    fn textbook_input(option_type: u8) -> GreeksInput {
+        GreeksInput {
+            option_type,
+            strike: 100_000_000_000,
+            expiry_date: ONE_YEAR,
+            current_ratio: 100_000_000_000,
+            now: 0,
+            contract_size: 1,
+            quantity: 1,
+        }
+    }
+
+    fn assert_close(actual: i64, expected: f64, tolerance: f64) {
+        let actual = actual as f64 / F;
+        assert!((actual - expected).abs() < tolerance, "{} vs {}", actual, expected);
+    }
+
+    #[test]
+    fn test_call_greeks_textbook_values() {
+        let greeks = calculate_greeks(&textbook_input(0), 200_000_000, 50_000_000).unwrap();
+        assert_close(greeks.delta, 0.6368, 1e-4);
+        assert_close(greeks.gamma, 0.018762, 1e-5);
+        assert_close(greeks.vega, 37.524, 1e-2);
+        assert_close(greeks.theta, -6.4140 / 365.0, 1e-4);
+        assert_close(greeks.rho, 53.232, 1e-2);
+    }
+
+    #[test]
+    fn test_put_greeks_textbook_values() {
+        let greeks = calculate_greeks(&textbook_input(1), 200_000_000, 50_000_000).unwrap();
+        assert_close(greeks.delta, -0.3632, 1e-4);
+        assert_close(greeks.gamma, 0.018762, 1e-5);
+        assert_close(greeks.vega, 37.524, 1e-2);
+        assert_close(greeks.theta, -1.6579 / 365.0, 1e-4);
+        assert_close(greeks.rho, -41.890, 1e-2);
+    }
+
+    #[test]
+    fn test_greeks_scale_with_position_size() {
+        let unit = calculate_greeks(&textbook_input(1), 200_000_000, 50_000_000).unwrap();
+        let mut input = textbook_input(1);
+        input.contract_size = 100;
+        input.quantity = 3;
+        let position = calculate_greeks(&input, 200_000_000, 50_000_000).unwrap();
+        assert_eq!(position.delta, unit.delta * 300);
+        assert_eq!(position.gamma, unit.gamma * 300);
+        assert_eq!(position.vega, unit.vega * 300);
+        assert_eq!(position.theta, unit.theta * 300);
+        assert_eq!(position.rho, unit.rho * 300);
+
+        input.quantity = 0;
+        assert_eq!(calculate_greeks(&input, 200_000_000, 50_000_000), Err(MathError::InvalidInput));
+    }
+
+    #[test]
+    fn test_expired_contract_has_no_greeks() {
+        let mut input = textbook_input(0);
+        input.now = input.expiry_date;
+        assert_eq!(calculate_greeks(&input, 200_000_000, 0), Err(MathError::InvalidInput));
+        input.now = input.expiry_date + 1;
+        assert_eq!(calculate_greeks(&input, 200_000_000, 0), Err(MathError::InvalidInput));
+        input.now = i64::MIN;
+        assert_eq!(calculate_greeks(&input, 200_000_000, 0), Err(MathError::Overflow));
+    }
+}
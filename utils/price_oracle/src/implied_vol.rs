@@ -0,0 +1,253 @@
+//! Implied volatility from premiums observed on-chain
+//!
+//! Backs out the Black-Scholes volatility that reproduces `OptionContract.price`
+//! at the current asset/SOL ratio, in the program's lamport/ratio units.
+
+use option_math::{
+    black_scholes_price, d1_d2, exp, mul, norm_pdf, sqrt, year_fraction, MathError, OptionKind,
+    PricingInput, SCALE,
+};
+use std::fmt;
+
+const MAX_ITERATIONS: u32 = 100;
+const MAX_VOLATILITY: i128 = 10 * SCALE; // 1000% annualized
+const INITIAL_VOLATILITY: i128 = SCALE / 2; // 50% starting guess
+const PRICE_TOLERANCE: i128 = 1; // Lamports per asset unit
+
+/// The contract fields needed to back out implied volatility, as stored in `OptionContract`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImpliedVolInput {
+    pub option_type: u8,    // 0: Call, 1: Put
+    pub price: u64,         // Premium for the whole contract in lamports
+    pub strike: u64,        // Strike (asset/SOL ratio in lamports)
+    pub expiry_date: i64,   // Expiry timestamp
+    pub contract_size: u64, // Units of the underlying per contract
+    pub quantity: u64,      // Number of contracts
+    pub current_ratio: u64, // Current asset/SOL ratio in lamports
+    pub now: i64,           // Valuation timestamp
+}
+
+/// Why implied volatility could not be found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpliedVolError {
+    /// Option type, size or dates cannot be priced
+    InvalidInput,
+    /// Premium per unit is outside the no-arbitrage bounds [lower, upper)
+    PriceOutOfBounds { price: u64, lower: u64, upper: u64 },
+    /// The solver ran out of iterations; last_estimate is the best volatility found
+    NoConvergence { iterations: u32, last_estimate: u64 },
+    /// Fixed-point arithmetic failed
+    Math(MathError),
+}
+
+impl fmt::Display for ImpliedVolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImpliedVolError::InvalidInput => write!(f, "invalid implied volatility input"),
+            ImpliedVolError::PriceOutOfBounds {
+                price,
+                lower,
+                upper,
+            } => write!(
+                f,
+                "premium {} per unit is outside the no-arbitrage bounds [{}, {})",
+                price, lower, upper
+            ),
+            ImpliedVolError::NoConvergence {
+                iterations,
+                last_estimate,
+            } => write!(
+                f,
+                "implied volatility did not converge after {} iterations (last estimate {})",
+                iterations, last_estimate
+            ),
+            ImpliedVolError::Math(error) => write!(f, "fixed-point math error: {:?}", error),
+        }
+    }
+}
+
+impl std::error::Error for ImpliedVolError {}
+
+impl From<MathError> for ImpliedVolError {
+    fn from(error: MathError) -> Self {
+        ImpliedVolError::Math(error)
+    }
+}
+
+/// Solve for the annualized volatility (1e9 = 100%) implied by the contract's premium
+/// rate: Annualized risk-free rate (1e9 = 100%)
+pub fn implied_volatility(input: &ImpliedVolInput, rate: i64) -> Result<u64, ImpliedVolError> {
+    solve(input, rate, MAX_ITERATIONS)
+}
+
+// Newton's method on volatility, falling back to bisection whenever a Newton
+// step leaves the bracket or vega vanishes. Volatilities too small for
+// sigma * sqrt(t) to register in fixed point are priced as zero volatility.
+fn solve(input: &ImpliedVolInput, rate: i64, max_iterations: u32) -> Result<u64, ImpliedVolError> {
+    let kind = OptionKind::from_u8(input.option_type).ok_or(ImpliedVolError::InvalidInput)?;
+    let time_to_expiry = input.expiry_date.checked_sub(input.now).ok_or(MathError::Overflow)?;
+    let units = (input.contract_size as u128) * (input.quantity as u128);
+    if time_to_expiry <= 0 || units == 0 || input.current_ratio == 0 || input.strike == 0 {
+        return Err(ImpliedVolError::InvalidInput);
+    }
+
+    let target = (input.price as u128 / units) as u64;
+    let t = year_fraction(time_to_expiry)?;
+    let sqrt_t = sqrt(t)?;
+    let price_at = |volatility: i128| {
+        let volatility = if mul(volatility, sqrt_t)? == 0 { 0 } else { volatility };
+        black_scholes_price(
+            kind,
+            &PricingInput {
+                spot: input.current_ratio,
+                strike: input.strike,
+                time_to_expiry,
+                volatility: volatility as u64,
+                rate,
+            },
+        )
+    };
+
+    // Zero volatility gives the lower bound; the spot (call) or discounted strike (put) the upper
+    let lower = price_at(0)?;
+    let upper = match kind {
+        OptionKind::Call => input.current_ratio,
+        OptionKind::Put => mul(input.strike as i128, exp(-mul(rate as i128, t)?)?)? as u64,
+    };
+    if target < lower || target >= upper {
+        return Err(ImpliedVolError::PriceOutOfBounds {
+            price: target,
+            lower,
+            upper,
+        });
+    }
+    if target == lower {
+        return Ok(0);
+    }
+
+    let spot = input.current_ratio as i128;
+    let target = target as i128;
+    let (mut low, mut high) = (0i128, MAX_VOLATILITY);
+    let mut volatility = INITIAL_VOLATILITY;
+
+    for _ in 0..max_iterations {
+        let diff = price_at(volatility)? as i128 - target;
+        if diff.abs() <= PRICE_TOLERANCE || high - low <= 1 {
+            return Ok(volatility as u64);
+        }
+        if diff > 0 {
+            high = volatility;
+        } else {
+            low = volatility;
+        }
+
+        let newton = if mul(volatility, sqrt_t)? > 0 {
+            let (d1, _) = d1_d2(spot, input.strike as i128, t, volatility, rate as i128)?;
+            let vega = mul(mul(spot, norm_pdf(d1)?)?, sqrt_t)?;
+            diff.checked_mul(SCALE)
+                .and_then(|scaled| scaled.checked_div(vega))
+                .and_then(|step| volatility.checked_sub(step))
+        } else {
+            None
+        };
+
+        volatility = match newton {
+            Some(next) if next > low && next < high => next,
+            _ => low + (high - low) / 2,
+        };
+    }
+
+    Err(ImpliedVolError::NoConvergence {
+        iterations: max_iterations,
+        last_estimate: volatility as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THIRTY_DAYS: i64 = 30 * 24 * 60 * 60;
+
+    fn input_at(option_type: u8, strike: u64, volatility: u64) -> ImpliedVolInput {
+        let kind = OptionKind::from_u8(option_type).unwrap();
+        let price = black_scholes_price(
+            kind,
+            &PricingInput {
+                spot: 1_500_000_000,
+                strike,
+                time_to_expiry: THIRTY_DAYS,
+                volatility,
+                rate: 0,
+            },
+        )
+        .unwrap();
+        ImpliedVolInput {
+            option_type,
+            price,
+            strike,
+            expiry_date: THIRTY_DAYS,
+            contract_size: 1,
+            quantity: 1,
+            current_ratio: 1_500_000_000,
+            now: 0,
+        }
+    }
+
+    #[test]
+    fn test_recovers_volatility_across_strikes() {
+        for (option_type, strike) in [
+            (0, 1_200_000_000),
+            (0, 1_500_000_000),
+            (1, 1_800_000_000),
+            (1, 1_400_000_000),
+        ] {
+            let input = input_at(option_type, strike, 350_000_000);
+            let volatility = implied_volatility(&input, 0).unwrap();
+            assert!(
+                (volatility as i64 - 350_000_000).abs() < 100_000,
+                "strike {} gave {}",
+                strike,
+                volatility
+            );
+        }
+    }
+
+    #[test]
+    fn test_scales_premium_by_contract_size() {
+        let mut input = input_at(0, 1_500_000_000, 600_000_000);
+        input.price *= 100;
+        input.contract_size = 100;
+        let volatility = implied_volatility(&input, 0).unwrap();
+        assert!((volatility as i64 - 600_000_000).abs() < 100_000);
+    }
+
+    #[test]
+    fn test_rejects_premium_below_intrinsic() {
+        // Call 0.3 SOL in the money priced at 0.1 SOL
+        let mut input = input_at(0, 1_200_000_000, 350_000_000);
+        input.price = 100_000_000;
+        assert!(matches!(
+            implied_volatility(&input, 0),
+            Err(ImpliedVolError::PriceOutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_solves_premiums_implying_near_zero_volatility() {
+        for volatility in [4, 20, 100, 1_000] {
+            let input = input_at(0, 1_500_000_000, volatility);
+            let implied = implied_volatility(&input, 0).unwrap();
+            assert!(implied < 10_000, "volatility {} gave {}", volatility, implied);
+        }
+    }
+
+    #[test]
+    fn test_reports_non_convergence() {
+        let input = input_at(0, 1_500_000_000, 350_000_000);
+        assert!(matches!(
+            solve(&input, 0, 1),
+            Err(ImpliedVolError::NoConvergence { iterations: 1, .. })
+        ));
+    }
+}
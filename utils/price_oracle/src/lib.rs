@@ -1,6 +1,28 @@
-use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
+
+mod error;
+pub use error::PriceOracleError;
+
+pub mod decimal;
+pub use decimal::Rounding;
+
+mod source;
+pub use source::{AlphaVantage, AsyncPriceSource, CoinGecko, PriceSource, ProviderConfig};
+
+/// Fixed-point Black-Scholes pricing shared with the on-chain program
+pub use option_math as pricing;
+
+pub mod aggregate;
+pub mod backtest;
+pub use backtest::{backtest, BacktestConfig, BacktestReport, Observation, OptionSpec};
+pub mod cache;
+pub use cache::{CacheConfig, CachedPriceSource};
+pub mod fixture;
+pub use fixture::{FixturePriceSource, Interpolation};
+pub mod simulate;
+pub use simulate::{PathModel, PathSimulator, PathSpec, SimulatedPath};
+pub mod greeks;
+pub mod implied_vol;
 
 /// Represents a price in USD with 6 decimal precision (lamports-style)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,127 +32,37 @@ pub struct Price {
     pub timestamp: i64,
 }
 
+/// Decimals of the program's asset/SOL ratio (lamports per asset unit)
+pub const SOL_DECIMALS: u8 = 9;
+
+/// Asset/SOL ratio in lamports per asset unit, identical to the program's settlement math
+/// Both prices are USD with 6 decimals; the result truncates exactly as on-chain
+pub fn asset_sol_ratio(asset_price_usd: u64, sol_price_usd: u64) -> Result<u64, pricing::MathError> {
+    pricing::ratio(asset_price_usd, sol_price_usd, SOL_DECIMALS, Rounding::Down)
+}
+
 /// Fetches the current price of SOL in USD from CoinGecko API
 /// Returns price in USD with 6 decimal precision (e.g., $50.00 = 50_000_000)
-pub fn fetch_sol_price() -> Result<u64, Box<dyn Error>> {
-    let client = Client::new();
-    let url = "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd";
-    
-    let response = client
-        .get(url)
-        .header("User-Agent", "Solana Options Escrow DApp")
-        .send()?;
-    
-    let json: serde_json::Value = response.json()?;
-    
-    let price_float = json["solana"]["usd"]
-        .as_f64()
-        .ok_or("Failed to parse SOL price")?;
-    
-    // Convert to 6 decimal precision (e.g., 50.123456 -> 50_123_456)
-    let price_lamports = (price_float * 1_000_000.0) as u64;
-    
-    Ok(price_lamports)
+pub fn fetch_sol_price() -> Result<u64, PriceOracleError> {
+    Ok(CoinGecko::new().spot("SOL")?.price_usd)
 }
 
 /// Fetches the current price of a stock symbol from Alpha Vantage API
 /// Note: You need to set ALPHA_VANTAGE_API_KEY environment variable
 /// Get free API key from: https://www.alphavantage.co/support/#api-key
-pub fn fetch_stock_price(symbol: &str) -> Result<u64, Box<dyn Error>> {
-    let api_key = std::env::var("ALPHA_VANTAGE_API_KEY")
-        .unwrap_or_else(|_| "demo".to_string());
-    
-    let client = Client::new();
-    let url = format!(
-        "https://www.alphavantage.co/query?function=GLOBAL_QUOTE&symbol={}&apikey={}",
-        symbol, api_key
-    );
-    
-    let response = client
-        .get(&url)
-        .header("User-Agent", "Solana Options Escrow DApp")
-        .send()?;
-    
-    let json: serde_json::Value = response.json()?;
-    
-    // Alpha Vantage returns data in "Global Quote" field
-    let price_str = json["Global Quote"]["05. price"]
-        .as_str()
-        .ok_or(format!("Failed to parse {} price. Check API key or rate limits.", symbol))?;
-    
-    let price_float: f64 = price_str.parse()?;
-    
-    // Convert to 6 decimal precision
-    let price_lamports = (price_float * 1_000_000.0) as u64;
-    
-    Ok(price_lamports)
+pub fn fetch_stock_price(symbol: &str) -> Result<u64, PriceOracleError> {
+    Ok(AlphaVantage::from_env().spot(symbol)?.price_usd)
 }
 
 /// Fetches historical stock price for a specific date using Alpha Vantage
 /// Date format: YYYY-MM-DD
-pub fn fetch_historical_stock_price(symbol: &str, date: &str) -> Result<u64, Box<dyn Error>> {
-    let api_key = std::env::var("ALPHA_VANTAGE_API_KEY")
-        .unwrap_or_else(|_| "demo".to_string());
-    
-    let client = Client::new();
-    let url = format!(
-        "https://www.alphavantage.co/query?function=TIME_SERIES_DAILY&symbol={}&apikey={}",
-        symbol, api_key
-    );
-    
-    let response = client
-        .get(&url)
-        .header("User-Agent", "Solana Options Escrow DApp")
-        .send()?;
-    
-    let json: serde_json::Value = response.json()?;
-    
-    // Get the closing price for the specific date
-    let price_str = json["Time Series (Daily)"][date]["4. close"]
-        .as_str()
-        .ok_or(format!("Failed to get historical price for {} on {}", symbol, date))?;
-    
-    let price_float: f64 = price_str.parse()?;
-    let price_lamports = (price_float * 1_000_000.0) as u64;
-    
-    Ok(price_lamports)
-}
-
-/// Mock function for testing - returns simulated prices for AAPL
-/// This is useful for testing without API dependencies
-pub fn mock_aapl_price(date: &str) -> Result<u64, Box<dyn Error>> {
-    // Simulated AAPL prices for August-September 2025
-    let prices = match date {
-        "2025-08-01" => 225.50,  // Initial price
-        "2025-08-05" => 228.75,  // Small gain
-        "2025-08-10" => 223.25,  // Small loss
-        "2025-08-15" => 230.50,  // Recovery
-        "2025-08-20" => 235.00,  // Strong gain
-        "2025-08-25" => 232.50,  // Slight pullback
-        "2025-08-30" => 238.75,  // Near expiry gain
-        "2025-09-01" => 240.00,  // Expiry price
-        _ => 225.50,  // Default
-    };
-    
-    Ok((prices * 1_000_000.0) as u64)
-}
-
-/// Mock function for testing - returns simulated SOL prices
-pub fn mock_sol_price(date: &str) -> Result<u64, Box<dyn Error>> {
-    // Simulated SOL prices for August-September 2025
-    let prices = match date {
-        "2025-08-01" => 150.00,  // Initial price
-        "2025-08-05" => 152.50,
-        "2025-08-10" => 148.00,
-        "2025-08-15" => 155.00,
-        "2025-08-20" => 160.00,
-        "2025-08-25" => 157.50,
-        "2025-08-30" => 162.00,
-        "2025-09-01" => 165.00,  // Expiry price
-        _ => 150.00,  // Default
-    };
-    
-    Ok((prices * 1_000_000.0) as u64)
+/// Set PRICE_ORACLE_CACHE to a file path to cache the whole daily series on disk
+pub fn fetch_historical_stock_price(symbol: &str, date: &str) -> Result<u64, PriceOracleError> {
+    let source = AlphaVantage::from_env();
+    match std::env::var("PRICE_ORACLE_CACHE") {
+        Ok(path) => Ok(CachedPriceSource::open(source, CacheConfig::new(path))?.historical(symbol, date)?.price_usd),
+        Err(_) => Ok(source.historical(symbol, date)?.price_usd),
+    }
 }
 
 #[cfg(test)]
@@ -138,21 +70,23 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_mock_prices() {
-        let aapl_price = mock_aapl_price("2025-08-01").unwrap();
+    fn test_fixture_prices() {
+        let fixtures = FixturePriceSource::sample();
+        let aapl_price = fixtures.historical("AAPL", "2025-08-01").unwrap().price_usd;
         assert_eq!(aapl_price, 225_500_000); // $225.50 in lamports
         
-        let sol_price = mock_sol_price("2025-08-01").unwrap();
+        let sol_price = fixtures.historical("SOL", "2025-08-01").unwrap().price_usd;
         assert_eq!(sol_price, 150_000_000); // $150.00 in lamports
     }
     
     #[test]
     fn test_price_ratio() {
-        let aapl = mock_aapl_price("2025-08-01").unwrap();
-        let sol = mock_sol_price("2025-08-01").unwrap();
+        let fixtures = FixturePriceSource::sample();
+        let aapl = fixtures.historical("AAPL", "2025-08-01").unwrap().price_usd;
+        let sol = fixtures.historical("SOL", "2025-08-01").unwrap().price_usd;
         
-        // AAPL/SOL = 225.50 / 150.00 = 1.503333
-        let ratio = (aapl as f64) / (sol as f64);
-        assert!((ratio - 1.503333).abs() < 0.001);
+        // AAPL/SOL = 225.50 / 150.00 = 1.503333333 SOL, truncated to the lamport as on-chain
+        assert_eq!(asset_sol_ratio(aapl, sol).unwrap(), 1_503_333_333);
+        assert_eq!(asset_sol_ratio(aapl, 0), Err(pricing::MathError::InvalidInput));
     }
 }
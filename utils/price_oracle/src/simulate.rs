@@ -0,0 +1,436 @@
+//! Seeded synthetic price paths for stress testing
+//!
+//! Generates daily asset and SOL prices from geometric Brownian motion,
+//! Merton-style jump diffusion or a flat path with scripted gaps. Everything
+//! runs in the program's fixed-point math with an in-crate generator, so a
+//! seed reproduces the same prices on every machine and the series can drive
+//! `daily_settlement` in tests deterministically.
+
+use crate::asset_sol_ratio;
+use crate::decimal::Rounding;
+use crate::fixture::{Bar, FixturePriceSource};
+use crate::pricing::ratio::div_round;
+use crate::pricing::{div, exp, ln, mul, sqrt, MathError, SCALE};
+use crate::source::start_of_day;
+use chrono::{Days, NaiveDate};
+
+const DAYS_PER_YEAR: i128 = 365;
+const BPS_DENOMINATOR: i128 = 10_000;
+const MIN_PRICE_USD: u64 = 1; // A crash floors at one micro-dollar so ratios stay defined
+
+/// How a price evolves between days
+/// Rates and volatilities are annualized and scaled by 1e9 (1e9 = 100%)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathModel {
+    /// Geometric Brownian motion
+    Gbm { drift: i64, volatility: u64 },
+    /// GBM plus normally distributed jumps in log price
+    JumpDiffusion {
+        drift: i64,
+        volatility: u64,
+        jump_intensity: u64,  // Expected jumps per year (1e9 = 1); at most one jump a day
+        jump_mean: i64,       // Mean log jump size (-1e8 is roughly a 9.5% drop)
+        jump_volatility: u64, // Standard deviation of the log jump size
+    },
+    /// Flat price that only moves on scripted shocks
+    Scripted,
+}
+
+/// A one-day gap applied after the model's move for that day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shock {
+    pub day: u32,        // Day index; day 0 is the start price and is never shocked
+    pub change_bps: i64, // Relative move (-4000 = 40% crash), floored at -10000
+}
+
+/// Starting price, model and scripted shocks for one series
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSpec {
+    pub start_price_usd: u64, // USD with 6 decimals
+    pub model: PathModel,
+    pub shocks: Vec<Shock>,
+}
+
+impl PathSpec {
+    pub fn new(start_price_usd: u64, model: PathModel) -> Self {
+        PathSpec {
+            start_price_usd,
+            model,
+            shocks: Vec::new(),
+        }
+    }
+
+    pub fn gbm(start_price_usd: u64, drift: i64, volatility: u64) -> Self {
+        PathSpec::new(start_price_usd, PathModel::Gbm { drift, volatility })
+    }
+
+    pub fn scripted(start_price_usd: u64) -> Self {
+        PathSpec::new(start_price_usd, PathModel::Scripted)
+    }
+
+    pub fn with_shock(mut self, day: u32, change_bps: i64) -> Self {
+        self.shocks.push(Shock { day, change_bps });
+        self
+    }
+}
+
+/// One simulated day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathPoint {
+    pub day: u32,
+    pub date: NaiveDate,
+    pub timestamp: i64,        // Start of day (UTC)
+    pub asset_price_usd: u64,  // USD with 6 decimals, as published to the asset feed
+    pub sol_price_usd: u64,    // USD with 6 decimals, as published to the SOL feed
+}
+
+impl PathPoint {
+    /// Asset/SOL ratio in lamports, exactly as the program settles it
+    pub fn ratio(&self) -> Result<u64, MathError> {
+        asset_sol_ratio(self.asset_price_usd, self.sol_price_usd)
+    }
+}
+
+/// Daily asset and SOL prices from day 0 to the last simulated day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedPath {
+    pub points: Vec<PathPoint>,
+}
+
+impl SimulatedPath {
+    /// Serve the path as daily bars (open = high = low = close) for two symbols
+    pub fn to_fixture(&self, asset_symbol: &str, sol_symbol: &str) -> FixturePriceSource {
+        let bar = |price: u64| Bar {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+        };
+        FixturePriceSource::new()
+            .with_bars(asset_symbol, self.points.iter().map(|point| (point.date, bar(point.asset_price_usd))))
+            .with_bars(sol_symbol, self.points.iter().map(|point| (point.date, bar(point.sol_price_usd))))
+    }
+
+    /// CSV of raw feed values for tests outside Rust
+    /// Columns: date,timestamp,asset_price_usd,sol_price_usd,ratio (ratio empty if undefined)
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("date,timestamp,asset_price_usd,sol_price_usd,ratio\n");
+        for point in &self.points {
+            let ratio = point.ratio().map(|ratio| ratio.to_string()).unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                point.date.format("%Y-%m-%d"),
+                point.timestamp,
+                point.asset_price_usd,
+                point.sol_price_usd,
+                ratio
+            ));
+        }
+        csv
+    }
+}
+
+/// Generates correlated asset and SOL paths from a seed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSimulator {
+    seed: u64,
+    start: NaiveDate,
+    days: u32,
+    asset: PathSpec,
+    sol: PathSpec,
+    correlation: i64, // Correlation of the daily diffusion shocks (1e9 = 1.0)
+}
+
+impl PathSimulator {
+    /// seed: Any value; the same seed and specs always give the same path
+    /// start: Date of day 0
+    /// days: Number of simulated days after day 0
+    pub fn new(seed: u64, start: NaiveDate, days: u32, asset: PathSpec, sol: PathSpec) -> Self {
+        PathSimulator {
+            seed,
+            start,
+            days,
+            asset,
+            sol,
+            correlation: 0,
+        }
+    }
+
+    /// Correlation between the asset's and SOL's diffusion shocks, clamped to [-1e9, 1e9]
+    pub fn with_correlation(mut self, correlation: i64) -> Self {
+        self.correlation = correlation.clamp(-SCALE as i64, SCALE as i64);
+        self
+    }
+
+    /// Run the simulation
+    /// Every day draws the same number of random values whatever the models are,
+    /// so two runs with one seed share their diffusion shocks across models
+    pub fn run(&self) -> Result<SimulatedPath, MathError> {
+        let mut rng = SplitMix64::new(self.seed);
+        let dt = SCALE / DAYS_PER_YEAR;
+        let sqrt_dt = sqrt(dt)?;
+        let correlation = self.correlation as i128;
+        let independent = sqrt(SCALE - mul(correlation, correlation)?)?;
+
+        let mut asset_price = self.asset.start_price_usd;
+        let mut sol_price = self.sol.start_price_usd;
+        let mut points = Vec::with_capacity(self.days as usize + 1);
+        for day in 0..=self.days {
+            if day > 0 {
+                let asset_shock = rng.next_normal()?;
+                let sol_shock = mul(correlation, asset_shock)? + mul(independent, rng.next_normal()?)?;
+                let asset_jump = (rng.next_unit(), rng.next_normal()?);
+                let sol_jump = (rng.next_unit(), rng.next_normal()?);
+                asset_price = step(asset_price, &self.asset, day, dt, sqrt_dt, asset_shock, asset_jump)?;
+                sol_price = step(sol_price, &self.sol, day, dt, sqrt_dt, sol_shock, sol_jump)?;
+            }
+
+            let date = self
+                .start
+                .checked_add_days(Days::new(day as u64))
+                .ok_or(MathError::Overflow)?;
+            points.push(PathPoint {
+                day,
+                date,
+                timestamp: start_of_day(date),
+                asset_price_usd: asset_price,
+                sol_price_usd: sol_price,
+            });
+        }
+
+        Ok(SimulatedPath { points })
+    }
+}
+
+// Helper function to move a price forward one day
+// shock: Standard normal diffusion draw
+// jump: (uniform in [0, 1) deciding whether a jump occurs, standard normal jump draw)
+fn step(
+    price: u64,
+    spec: &PathSpec,
+    day: u32,
+    dt: i128,
+    sqrt_dt: i128,
+    shock: i128,
+    jump: (i128, i128),
+) -> Result<u64, MathError> {
+    let diffusion = |drift: i64, volatility: u64| -> Result<i128, MathError> {
+        let volatility = volatility as i128;
+        let drift_term = mul(drift as i128 - mul(volatility, volatility)? / 2, dt)?;
+        Ok(drift_term + mul(mul(volatility, sqrt_dt)?, shock)?)
+    };
+    let log_return = match spec.model {
+        PathModel::Gbm { drift, volatility } => diffusion(drift, volatility)?,
+        PathModel::JumpDiffusion {
+            drift,
+            volatility,
+            jump_intensity,
+            jump_mean,
+            jump_volatility,
+        } => {
+            let (draw, size) = jump;
+            let jump_probability = mul(jump_intensity as i128, dt)?;
+            let jump_term = if draw < jump_probability {
+                jump_mean as i128 + mul(jump_volatility as i128, size)?
+            } else {
+                0
+            };
+            diffusion(drift, volatility)? + jump_term
+        }
+        PathModel::Scripted => 0,
+    };
+
+    let growth = exp(log_return)? as u128;
+    let grown = (price as u128).checked_mul(growth).ok_or(MathError::Overflow)?;
+    let mut next = div_round(grown, SCALE as u128, Rounding::HalfEven);
+    for shock in spec.shocks.iter().filter(|shock| shock.day == day) {
+        let factor = (BPS_DENOMINATOR + shock.change_bps as i128).max(0) as u128;
+        let shocked = next.checked_mul(factor).ok_or(MathError::Overflow)?;
+        next = div_round(shocked, BPS_DENOMINATOR as u128, Rounding::HalfEven);
+    }
+    let next = u64::try_from(next).map_err(|_| MathError::Overflow)?;
+    Ok(next.max(MIN_PRICE_USD))
+}
+
+// SplitMix64: small, fast and fully specified, so seeds stay stable across
+// platforms and dependency upgrades
+#[derive(Debug, Clone)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1) in fixed point
+    fn next_unit(&mut self) -> i128 {
+        ((self.next_u64() >> 32) as i128 * SCALE) >> 32
+    }
+
+    // Standard normal in fixed point by the Marsaglia polar method
+    // Needs only ln and sqrt; each attempt is accepted with probability pi/4
+    fn next_normal(&mut self) -> Result<i128, MathError> {
+        loop {
+            let u = 2 * self.next_unit() - SCALE;
+            let v = 2 * self.next_unit() - SCALE;
+            let s = mul(u, u)? + mul(v, v)?;
+            if s == 0 || s >= SCALE {
+                continue;
+            }
+            let factor = sqrt(div(-2 * ln(s)?, s)?)?;
+            return mul(u, factor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PriceOracleError, PriceSource};
+
+    fn start() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 8, 1).unwrap()
+    }
+
+    fn volatile() -> PathSimulator {
+        PathSimulator::new(
+            42,
+            start(),
+            30,
+            PathSpec::gbm(225_500_000, 50_000_000, 300_000_000),
+            PathSpec::gbm(150_000_000, 0, 800_000_000),
+        )
+        .with_correlation(500_000_000)
+    }
+
+    #[test]
+    fn test_seed_reproduces_path() {
+        let path = volatile().run().unwrap();
+        assert_eq!(path, volatile().run().unwrap());
+        assert_eq!(path.points.len(), 31);
+        assert_eq!(path.points[0].asset_price_usd, 225_500_000);
+        assert_eq!(path.points[0].timestamp, 1_754_006_400); // 2025-08-01T00:00:00Z
+        assert_eq!(path.points[30].date, NaiveDate::from_ymd_opt(2025, 8, 31).unwrap());
+
+        let mut other = volatile();
+        other.seed = 43;
+        assert_ne!(path, other.run().unwrap());
+    }
+
+    #[test]
+    fn test_scripted_gap_crash() {
+        let path = PathSimulator::new(
+            7,
+            start(),
+            10,
+            PathSpec::scripted(225_500_000).with_shock(3, 1_000).with_shock(5, -4_000),
+            PathSpec::scripted(150_000_000).with_shock(5, -10_000),
+        )
+        .run()
+        .unwrap();
+
+        let asset: Vec<u64> = path.points.iter().map(|point| point.asset_price_usd).collect();
+        assert_eq!(asset[..3], [225_500_000; 3]);
+        assert_eq!(asset[3..5], [248_050_000; 2]); // +10%
+        assert_eq!(asset[5..], [148_830_000; 6]); // -40%
+        // A total SOL wipeout floors at one micro-dollar instead of zero
+        assert_eq!(path.points[5].sol_price_usd, 1);
+        assert_eq!(path.points[5].ratio(), Ok(148_830_000_000_000_000));
+    }
+
+    #[test]
+    fn test_extreme_shocks_overflow_instead_of_wrapping() {
+        // Two maximal shocks on one day overflow the intermediate product
+        let shocked = PathSpec::scripted(u64::MAX).with_shock(1, i64::MAX).with_shock(1, i64::MAX);
+        let simulator = PathSimulator::new(7, start(), 2, shocked, PathSpec::scripted(150_000_000));
+        assert_eq!(simulator.run(), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn test_deterministic_models() {
+        // No volatility: GBM compounds its drift, 36.5% a year is ~0.1% a day
+        let drift_only = PathSimulator::new(
+            1,
+            start(),
+            10,
+            PathSpec::gbm(100_000_000, 365_000_000, 0),
+            PathSpec::scripted(100_000_000),
+        )
+        .run()
+        .unwrap();
+        let expected = 100.0 * (10.0f64 * 0.001).exp();
+        assert!((drift_only.points[10].asset_price_usd as f64 / 1e6 - expected).abs() < 1e-4);
+        assert!(drift_only.points.iter().all(|point| point.sol_price_usd == 100_000_000));
+
+        // A jump every day of exactly -0.1 in log price
+        let jumps = PathSpec::new(
+            100_000_000,
+            PathModel::JumpDiffusion {
+                drift: 0,
+                volatility: 0,
+                jump_intensity: 365 * SCALE as u64,
+                jump_mean: -100_000_000,
+                jump_volatility: 0,
+            },
+        );
+        let path = PathSimulator::new(1, start(), 5, jumps, PathSpec::scripted(100_000_000)).run().unwrap();
+        let expected = 100.0 * (-0.5f64).exp();
+        assert!((path.points[5].asset_price_usd as f64 / 1e6 - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_gbm_volatility_and_correlation() {
+        let days = 2_000;
+        let spec = PathSpec::gbm(100_000_000, 0, 400_000_000);
+        let path = PathSimulator::new(9, start(), days, spec.clone(), spec)
+            .with_correlation(SCALE as i64)
+            .run()
+            .unwrap();
+
+        let returns: Vec<f64> = path
+            .points
+            .windows(2)
+            .map(|pair| (pair[1].asset_price_usd as f64 / pair[0].asset_price_usd as f64).ln())
+            .collect();
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+        let annualized = (variance * 365.0).sqrt();
+        assert!((annualized - 0.4).abs() < 0.04, "annualized volatility {}", annualized);
+
+        // Perfect correlation with identical specs moves both series together
+        assert!(path.points.iter().all(|point| point.asset_price_usd == point.sol_price_usd));
+    }
+
+    #[test]
+    fn test_path_as_fixture_and_csv() {
+        let path = volatile().run().unwrap();
+        let fixture = path.to_fixture("AAPL", "SOL");
+        let point = path.points[12];
+        assert_eq!(fixture.historical("AAPL", "2025-08-13").unwrap().price_usd, point.asset_price_usd);
+        assert_eq!(fixture.historical("SOL", "2025-08-13").unwrap().price_usd, point.sol_price_usd);
+        assert!(matches!(fixture.historical("AAPL", "2025-09-01"), Err(PriceOracleError::MissingDate { .. })));
+
+        let csv = path.to_csv();
+        let row = csv.lines().nth(13).unwrap();
+        assert_eq!(
+            row,
+            format!(
+                "2025-08-13,{},{},{},{}",
+                point.timestamp,
+                point.asset_price_usd,
+                point.sol_price_usd,
+                point.ratio().unwrap()
+            )
+        );
+    }
+}
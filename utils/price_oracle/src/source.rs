@@ -0,0 +1,577 @@
+//! Pluggable price providers
+//!
+//! Keepers and tests hold a `Box<dyn PriceSource>` so CoinGecko, Alpha Vantage
+//! and `FixturePriceSource` can be swapped without touching calling code. Async
+//! keepers use `AsyncPriceSource`; the blocking trait wraps it. The HTTP
+//! providers are tested against a local mock server through `ProviderConfig`.
+
+use crate::decimal::{parse_price, Rounding};
+use crate::{Price, PriceOracleError};
+use chrono::{NaiveDate, Utc};
+use futures_util::future::join_all;
+use reqwest::{header::USER_AGENT, StatusCode};
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+
+const COINGECKO_BASE_URL: &str = "https://api.coingecko.com/api/v3";
+const ALPHA_VANTAGE_BASE_URL: &str = "https://www.alphavantage.co";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_USER_AGENT: &str = "Solana Options Escrow DApp";
+const PROVIDER_ROUNDING: Rounding = Rounding::HalfEven; // Unbiased for quotes beyond 6 decimals
+
+/// HTTP settings for a provider, so it can be pointed at a local stand-in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderConfig {
+    pub base_url: String,   // Scheme and host plus any path prefix, without trailing slash
+    pub timeout: Duration,  // Per-request timeout
+    pub user_agent: String, // User-Agent header sent with every request
+}
+
+impl ProviderConfig {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        ProviderConfig {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+}
+
+/// A provider of USD prices with 6 decimal precision
+/// Blocking implementations must not be called from inside an async runtime
+pub trait PriceSource {
+    /// Latest price for the symbol
+    fn spot(&self, symbol: &str) -> Result<Price, PriceOracleError>;
+
+    /// Closing price for the symbol on a date (YYYY-MM-DD)
+    fn historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError>;
+
+    /// Every daily close the provider returns in one request, oldest first
+    /// Sources without a bulk endpoint return an empty series and are queried date by date
+    fn daily_series(&self, _symbol: &str) -> Result<Vec<Price>, PriceOracleError> {
+        Ok(Vec::new())
+    }
+}
+
+/// Async counterpart of `PriceSource` for keepers running on tokio
+pub trait AsyncPriceSource: Sync {
+    /// Latest price for the symbol
+    fn fetch_spot(&self, symbol: &str) -> impl Future<Output = Result<Price, PriceOracleError>> + Send;
+
+    /// Closing price for the symbol on a date (YYYY-MM-DD)
+    fn fetch_historical(
+        &self,
+        symbol: &str,
+        date: &str,
+    ) -> impl Future<Output = Result<Price, PriceOracleError>> + Send;
+
+    /// Latest prices for several symbols, requested concurrently
+    /// Results are returned in the order of `symbols`
+    fn fetch_spot_many(
+        &self,
+        symbols: &[&str],
+    ) -> impl Future<Output = Vec<Result<Price, PriceOracleError>>> + Send {
+        join_all(symbols.iter().map(|symbol| self.fetch_spot(symbol)))
+    }
+}
+
+/// CoinGecko public API, for crypto assets such as SOL
+#[derive(Debug, Clone)]
+pub struct CoinGecko {
+    config: ProviderConfig,
+    http: reqwest::Client,
+}
+
+impl CoinGecko {
+    pub fn new() -> Self {
+        CoinGecko::with_config(ProviderConfig::new(COINGECKO_BASE_URL))
+    }
+
+    pub fn with_config(config: ProviderConfig) -> Self {
+        CoinGecko::with_client(config, reqwest::Client::new())
+    }
+
+    /// Use a caller-owned connection pool for async fetches, e.g. one shared with other providers
+    /// Blocking calls always use the pool owned by the blocking runtime
+    pub fn with_client(config: ProviderConfig, http: reqwest::Client) -> Self {
+        CoinGecko { config, http }
+    }
+
+    async fn spot_with(&self, http: &reqwest::Client, symbol: &str) -> Result<Price, PriceOracleError> {
+        let id = coingecko_id(symbol);
+        let url = format!(
+            "{}/simple/price?ids={}&vs_currencies=usd",
+            self.config.base_url, id
+        );
+        let json = fetch_json(http, &self.config, &url).await?;
+        let price_usd = parse_coingecko_spot(&json, &id, symbol)?;
+        Ok(price(symbol, price_usd, Utc::now().timestamp()))
+    }
+
+    async fn historical_with(
+        &self,
+        http: &reqwest::Client,
+        symbol: &str,
+        date: &str,
+    ) -> Result<Price, PriceOracleError> {
+        let day = parse_date(date)?;
+        let id = coingecko_id(symbol);
+        // CoinGecko expects dd-mm-yyyy
+        let url = format!(
+            "{}/coins/{}/history?date={}&localization=false",
+            self.config.base_url,
+            id,
+            day.format("%d-%m-%Y")
+        );
+        let json = fetch_json(http, &self.config, &url).await?;
+        let price_usd = parse_coingecko_historical(&json, symbol, date)?;
+        Ok(price(symbol, price_usd, start_of_day(day)))
+    }
+}
+
+impl Default for CoinGecko {
+    fn default() -> Self {
+        CoinGecko::new()
+    }
+}
+
+impl AsyncPriceSource for CoinGecko {
+    async fn fetch_spot(&self, symbol: &str) -> Result<Price, PriceOracleError> {
+        self.spot_with(&self.http, symbol).await
+    }
+
+    async fn fetch_historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError> {
+        self.historical_with(&self.http, symbol, date).await
+    }
+}
+
+impl PriceSource for CoinGecko {
+    fn spot(&self, symbol: &str) -> Result<Price, PriceOracleError> {
+        block_on(|http| self.spot_with(http, symbol))
+    }
+
+    fn historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError> {
+        block_on(|http| self.historical_with(http, symbol, date))
+    }
+}
+
+/// Alpha Vantage API, for equities such as AAPL
+/// Get free API key from: https://www.alphavantage.co/support/#api-key
+#[derive(Debug, Clone)]
+pub struct AlphaVantage {
+    api_key: String,
+    config: ProviderConfig,
+    http: reqwest::Client,
+}
+
+impl AlphaVantage {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        AlphaVantage::with_config(api_key, ProviderConfig::new(ALPHA_VANTAGE_BASE_URL))
+    }
+
+    pub fn with_config(api_key: impl Into<String>, config: ProviderConfig) -> Self {
+        AlphaVantage::with_client(api_key, config, reqwest::Client::new())
+    }
+
+    /// Use a caller-owned connection pool for async fetches, e.g. one shared with other providers
+    /// Blocking calls always use the pool owned by the blocking runtime
+    pub fn with_client(api_key: impl Into<String>, config: ProviderConfig, http: reqwest::Client) -> Self {
+        AlphaVantage {
+            api_key: api_key.into(),
+            config,
+            http,
+        }
+    }
+
+    /// Reads ALPHA_VANTAGE_API_KEY, falling back to the rate-limited "demo" key
+    pub fn from_env() -> Self {
+        AlphaVantage::new(std::env::var("ALPHA_VANTAGE_API_KEY").unwrap_or_else(|_| "demo".to_string()))
+    }
+}
+
+impl AsyncPriceSource for AlphaVantage {
+    async fn fetch_spot(&self, symbol: &str) -> Result<Price, PriceOracleError> {
+        self.spot_with(&self.http, symbol).await
+    }
+
+    async fn fetch_historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError> {
+        self.historical_with(&self.http, symbol, date).await
+    }
+}
+
+impl AlphaVantage {
+    /// Every daily close in one TIME_SERIES_DAILY response (the last 100 trading days), oldest first
+    pub async fn fetch_daily_series(&self, symbol: &str) -> Result<Vec<Price>, PriceOracleError> {
+        self.daily_series_with(&self.http, symbol).await
+    }
+
+    async fn spot_with(&self, http: &reqwest::Client, symbol: &str) -> Result<Price, PriceOracleError> {
+        let url = format!(
+            "{}/query?function=GLOBAL_QUOTE&symbol={}&apikey={}",
+            self.config.base_url, symbol, self.api_key
+        );
+        let json = fetch_json(http, &self.config, &url).await?;
+        let price_usd = parse_stock_price(&json, symbol)?;
+        Ok(price(symbol, price_usd, Utc::now().timestamp()))
+    }
+
+    async fn historical_with(
+        &self,
+        http: &reqwest::Client,
+        symbol: &str,
+        date: &str,
+    ) -> Result<Price, PriceOracleError> {
+        let day = parse_date(date)?;
+        let json = self.fetch_time_series_daily(http, symbol).await?;
+        let price_usd = parse_historical_stock_price(&json, symbol, date)?;
+        Ok(price(symbol, price_usd, start_of_day(day)))
+    }
+
+    async fn daily_series_with(&self, http: &reqwest::Client, symbol: &str) -> Result<Vec<Price>, PriceOracleError> {
+        let json = self.fetch_time_series_daily(http, symbol).await?;
+        parse_daily_series(&json, symbol)
+    }
+
+    async fn fetch_time_series_daily(
+        &self,
+        http: &reqwest::Client,
+        symbol: &str,
+    ) -> Result<serde_json::Value, PriceOracleError> {
+        let url = format!(
+            "{}/query?function=TIME_SERIES_DAILY&symbol={}&apikey={}",
+            self.config.base_url, symbol, self.api_key
+        );
+        fetch_json(http, &self.config, &url).await
+    }
+}
+
+impl PriceSource for AlphaVantage {
+    fn spot(&self, symbol: &str) -> Result<Price, PriceOracleError> {
+        block_on(|http| self.spot_with(http, symbol))
+    }
+
+    fn historical(&self, symbol: &str, date: &str) -> Result<Price, PriceOracleError> {
+        block_on(|http| self.historical_with(http, symbol, date))
+    }
+
+    fn daily_series(&self, symbol: &str) -> Result<Vec<Price>, PriceOracleError> {
+        block_on(|http| self.daily_series_with(http, symbol))
+    }
+}
+
+fn price(symbol: &str, price_usd: u64, timestamp: i64) -> Price {
+    Price {
+        symbol: symbol.to_string(),
+        price_usd,
+        timestamp,
+    }
+}
+
+// Helper function to map ticker symbols to CoinGecko coin ids
+fn coingecko_id(symbol: &str) -> String {
+    match symbol.to_uppercase().as_str() {
+        "SOL" => "solana".to_string(),
+        "BTC" => "bitcoin".to_string(),
+        "ETH" => "ethereum".to_string(),
+        "USDC" => "usd-coin".to_string(),
+        _ => symbol.to_lowercase(),
+    }
+}
+
+pub(crate) fn parse_date(date: &str) -> Result<NaiveDate, PriceOracleError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| PriceOracleError::InvalidDate(date.to_string()))
+}
+
+pub(crate) fn start_of_day(day: NaiveDate) -> i64 {
+    day.and_hms_opt(0, 0, 0).map(|midnight| midnight.and_utc().timestamp()).unwrap_or_default()
+}
+
+// Helper function to drive an async fetch from blocking code
+// The runtime lives for the whole process so pooled connections survive between calls.
+// It owns its connection pool: connections are bound to the runtime that opened them,
+// so a pool shared with callers' runtimes could hand it connections whose runtime is gone.
+fn block_on<F, Fut>(fetch: F) -> Fut::Output
+where
+    F: FnOnce(&'static reqwest::Client) -> Fut,
+    Fut: Future,
+{
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    static HTTP: OnceLock<reqwest::Client> = OnceLock::new();
+    let runtime = RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("price-oracle-blocking")
+            .enable_all()
+            .build()
+            .expect("failed to start price_oracle runtime")
+    });
+    runtime.block_on(fetch(HTTP.get_or_init(reqwest::Client::new)))
+}
+
+// Helper function to GET a URL and decode the body as JSON
+// HTTP 429 is reported as RateLimited, other non-success statuses as Network.
+// Errors never carry the query string, which holds the Alpha Vantage API key.
+async fn fetch_json(
+    http: &reqwest::Client,
+    config: &ProviderConfig,
+    url: &str,
+) -> Result<serde_json::Value, PriceOracleError> {
+    let response = http
+        .get(url)
+        .timeout(config.timeout)
+        .header(USER_AGENT, config.user_agent.as_str())
+        .send()
+        .await
+        .map_err(reqwest::Error::without_url)?;
+
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        return Err(PriceOracleError::RateLimited(format!("HTTP 429 from {}", without_query(url))));
+    }
+    let body = response
+        .error_for_status()
+        .map_err(reqwest::Error::without_url)?
+        .text()
+        .await
+        .map_err(reqwest::Error::without_url)?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+// Helper function to strip the query string from a URL before it is reported
+fn without_query(url: &str) -> &str {
+    url.split_once('?').map_or(url, |(base, _)| base)
+}
+
+// Helper function to classify CoinGecko's throttling payload, {"status": {"error_code": 429, ...}}
+fn check_coingecko_errors(json: &serde_json::Value) -> Result<(), PriceOracleError> {
+    if json["status"]["error_code"].as_u64() == Some(429) {
+        let message = json["status"]["error_message"].as_str().unwrap_or("CoinGecko rate limit");
+        return Err(PriceOracleError::RateLimited(message.to_string()));
+    }
+    Ok(())
+}
+
+fn parse_coingecko_spot(json: &serde_json::Value, id: &str, symbol: &str) -> Result<u64, PriceOracleError> {
+    check_coingecko_errors(json)?;
+    if json.get(id).is_none() {
+        return Err(PriceOracleError::MissingSymbol(symbol.to_string()));
+    }
+
+    let price_number = json[id]["usd"]
+        .as_number()
+        .ok_or_else(|| PriceOracleError::MalformedResponse(format!("{}.usd is not a number", id)))?;
+
+    // With arbitrary_precision the number renders as the provider's own digits, never via f64
+    parse_price_str(&price_number.to_string())
+}
+
+fn parse_coingecko_historical(json: &serde_json::Value, symbol: &str, date: &str) -> Result<u64, PriceOracleError> {
+    check_coingecko_errors(json)?;
+    if json.get("error").is_some() {
+        return Err(PriceOracleError::MissingSymbol(symbol.to_string()));
+    }
+    // Coins listed after the date return no market_data
+    let market_data = json.get("market_data").ok_or_else(|| PriceOracleError::MissingDate {
+        symbol: symbol.to_string(),
+        date: date.to_string(),
+    })?;
+
+    let price_number = market_data["current_price"]["usd"]
+        .as_number()
+        .ok_or_else(|| PriceOracleError::MalformedResponse("current_price.usd is not a number".to_string()))?;
+    parse_price_str(&price_number.to_string())
+}
+
+fn parse_stock_price(json: &serde_json::Value, symbol: &str) -> Result<u64, PriceOracleError> {
+    check_alpha_vantage_errors(json, symbol)?;
+
+    // Alpha Vantage returns data in "Global Quote" field, empty for unknown symbols
+    let quote = json
+        .get("Global Quote")
+        .and_then(|quote| quote.as_object())
+        .ok_or_else(|| PriceOracleError::MalformedResponse("missing Global Quote".to_string()))?;
+    if quote.is_empty() {
+        return Err(PriceOracleError::MissingSymbol(symbol.to_string()));
+    }
+
+    let price_str = quote
+        .get("05. price")
+        .and_then(|price| price.as_str())
+        .ok_or_else(|| PriceOracleError::MalformedResponse(format!("missing price for {}", symbol)))?;
+
+    parse_price_str(price_str)
+}
+
+fn parse_historical_stock_price(
+    json: &serde_json::Value,
+    symbol: &str,
+    date: &str,
+) -> Result<u64, PriceOracleError> {
+    check_alpha_vantage_errors(json, symbol)?;
+
+    let series = json
+        .get("Time Series (Daily)")
+        .ok_or_else(|| PriceOracleError::MalformedResponse("missing Time Series (Daily)".to_string()))?;
+
+    // Get the closing price for the specific date
+    let day = series.get(date).ok_or_else(|| PriceOracleError::MissingDate {
+        symbol: symbol.to_string(),
+        date: date.to_string(),
+    })?;
+    let price_str = day["4. close"]
+        .as_str()
+        .ok_or_else(|| PriceOracleError::MalformedResponse(format!("missing close for {} on {}", symbol, date)))?;
+
+    parse_price_str(price_str)
+}
+
+fn parse_daily_series(json: &serde_json::Value, symbol: &str) -> Result<Vec<Price>, PriceOracleError> {
+    check_alpha_vantage_errors(json, symbol)?;
+
+    let series = json
+        .get("Time Series (Daily)")
+        .and_then(|series| series.as_object())
+        .ok_or_else(|| PriceOracleError::MalformedResponse("missing Time Series (Daily)".to_string()))?;
+
+    let mut prices = series
+        .iter()
+        .map(|(date, day)| {
+            let close = day["4. close"]
+                .as_str()
+                .ok_or_else(|| PriceOracleError::MalformedResponse(format!("missing close for {} on {}", symbol, date)))?;
+            Ok(price(symbol, parse_price_str(close)?, start_of_day(parse_date(date)?)))
+        })
+        .collect::<Result<Vec<Price>, PriceOracleError>>()?;
+    prices.sort_by_key(|price| price.timestamp);
+    Ok(prices)
+}
+
+// Helper function to classify Alpha Vantage's in-band error payloads, which arrive with HTTP 200
+fn check_alpha_vantage_errors(json: &serde_json::Value, symbol: &str) -> Result<(), PriceOracleError> {
+    for key in ["Note", "Information"] {
+        if let Some(message) = json[key].as_str() {
+            return Err(PriceOracleError::RateLimited(message.to_string()));
+        }
+    }
+    if json.get("Error Message").is_some() {
+        return Err(PriceOracleError::MissingSymbol(symbol.to_string()));
+    }
+    Ok(())
+}
+
+// Helper function to convert a decimal price string to 6 decimal precision
+pub(crate) fn parse_price_str(price_str: &str) -> Result<u64, PriceOracleError> {
+    parse_price(price_str, PROVIDER_ROUNDING).map_err(|error| PriceOracleError::InvalidPrice {
+        value: price_str.to_string(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decimal::DecimalError;
+
+    #[test]
+    fn test_sources_are_interchangeable() {
+        let sources: Vec<Box<dyn PriceSource>> = vec![
+            Box::new(crate::FixturePriceSource::sample()),
+            Box::new(CoinGecko::new()),
+            Box::new(AlphaVantage::new("demo")),
+        ];
+        // Only the fixtures are queried; the others just need to fit behind the trait
+        let price = sources[0].historical("AAPL", "2025-08-01").unwrap();
+        assert_eq!(price.symbol, "AAPL");
+        assert_eq!(price.price_usd, 225_500_000);
+        assert_eq!(price.timestamp, 1_754_006_400); // 2025-08-01T00:00:00Z
+    }
+
+    #[test]
+    fn test_alpha_vantage_rate_limit() {
+        let note = serde_json::json!({ "Note": "Thank you for using Alpha Vantage! Our standard API call frequency is 5 calls per minute" });
+        assert!(matches!(parse_stock_price(&note, "AAPL"), Err(PriceOracleError::RateLimited(_))));
+
+        let information = serde_json::json!({ "Information": "The **demo** API key is for demo purposes only" });
+        assert!(matches!(
+            parse_historical_stock_price(&information, "AAPL", "2025-08-01"),
+            Err(PriceOracleError::RateLimited(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_symbol_and_date() {
+        let empty_quote = serde_json::json!({ "Global Quote": {} });
+        assert!(matches!(parse_stock_price(&empty_quote, "NOPE"), Err(PriceOracleError::MissingSymbol(s)) if s == "NOPE"));
+
+        let invalid_call = serde_json::json!({ "Error Message": "Invalid API call" });
+        assert!(matches!(parse_stock_price(&invalid_call, "NOPE"), Err(PriceOracleError::MissingSymbol(_))));
+
+        let series = serde_json::json!({ "Time Series (Daily)": { "2025-08-01": { "4. close": "225.5000" } } });
+        assert_eq!(parse_historical_stock_price(&series, "AAPL", "2025-08-01").unwrap(), 225_500_000);
+        assert!(matches!(
+            parse_historical_stock_price(&series, "AAPL", "2025-08-02"),
+            Err(PriceOracleError::MissingDate { .. })
+        ));
+
+        assert!(matches!(
+            parse_coingecko_spot(&serde_json::json!({}), "solana", "SOL"),
+            Err(PriceOracleError::MissingSymbol(_))
+        ));
+        assert!(matches!(
+            parse_coingecko_historical(&serde_json::json!({ "id": "solana" }), "SOL", "2015-01-01"),
+            Err(PriceOracleError::MissingDate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_coingecko_prices() {
+        let spot = serde_json::json!({ "solana": { "usd": 150.25 } });
+        assert_eq!(parse_coingecko_spot(&spot, "solana", "SOL").unwrap(), 150_250_000);
+
+        // 2.01 * 1e6 as u64 is 2_009_999
+        let cents = serde_json::json!({ "jupiter": { "usd": 2.01 } });
+        assert_eq!(parse_coingecko_spot(&cents, "jupiter", "JUP").unwrap(), 2_010_000);
+        let micro = serde_json::from_str(r#"{ "bonk": { "usd": 2.35e-5 } }"#).unwrap();
+        assert_eq!(parse_coingecko_spot(&micro, "bonk", "BONK").unwrap(), 24);
+
+        // Just above a half-even tie; an f64 round-trip would land on the tie and round down
+        let precise = serde_json::from_str(r#"{ "solana": { "usd": 150.25000050000000001 } }"#).unwrap();
+        assert_eq!(parse_coingecko_spot(&precise, "solana", "SOL").unwrap(), 150_250_001);
+
+        let history = serde_json::json!({ "id": "solana", "market_data": { "current_price": { "usd": 165.0 } } });
+        assert_eq!(parse_coingecko_historical(&history, "SOL", "2025-09-01").unwrap(), 165_000_000);
+
+        let throttled = serde_json::json!({ "status": { "error_code": 429, "error_message": "You've exceeded the Rate Limit" } });
+        assert!(matches!(parse_coingecko_spot(&throttled, "solana", "SOL"), Err(PriceOracleError::RateLimited(_))));
+    }
+
+    #[test]
+    fn test_malformed_response() {
+        let quote = serde_json::json!({ "Global Quote": { "05. price": "n/a" } });
+        assert!(matches!(parse_stock_price(&quote, "AAPL"), Err(PriceOracleError::InvalidPrice { .. })));
+
+        let negative = serde_json::json!({ "solana": { "usd": -1.5 } });
+        assert!(matches!(
+            parse_coingecko_spot(&negative, "solana", "SOL"),
+            Err(PriceOracleError::InvalidPrice { error: DecimalError::Negative, .. })
+        ));
+
+        let sol = serde_json::json!({ "solana": { "usd": "150" } });
+        assert!(matches!(parse_coingecko_spot(&sol, "solana", "SOL"), Err(PriceOracleError::MalformedResponse(_))));
+
+        let not_json: Result<serde_json::Value, PriceOracleError> =
+            serde_json::from_str("<html>").map_err(PriceOracleError::from);
+        assert!(matches!(not_json, Err(PriceOracleError::MalformedResponse(_))));
+    }
+}
@@ -0,0 +1,88 @@
+//! Async provider tests against a local mock HTTP server
+
+use mockito::{Matcher, Server};
+use price_oracle::{AlphaVantage, AsyncPriceSource, CoinGecko, FixturePriceSource, PriceOracleError, ProviderConfig};
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("missing fixture {}", path))
+}
+
+fn global_quote(symbol: &str, price: &str) -> String {
+    fixture("alpha_vantage_global_quote.json")
+        .replace("\"AAPL\"", &format!("\"{}\"", symbol))
+        .replace("\"228.7500\"", &format!("\"{}\"", price))
+}
+
+#[tokio::test]
+async fn test_fetch_spot_and_historical() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/simple/price")
+        .match_query(Matcher::UrlEncoded("ids".into(), "solana".into()))
+        .with_body(fixture("coingecko_simple_price.json"))
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/coins/solana/history")
+        .match_query(Matcher::Any)
+        .with_body(fixture("coingecko_history.json"))
+        .create_async()
+        .await;
+
+    let source = CoinGecko::with_config(ProviderConfig::new(server.url()));
+    assert_eq!(source.fetch_spot("SOL").await.unwrap().price_usd, 150_250_000);
+    assert_eq!(source.fetch_historical("SOL", "2025-09-01").await.unwrap().price_usd, 165_000_000);
+}
+
+#[tokio::test]
+async fn test_fetch_spot_many_keeps_symbol_order() {
+    let mut server = Server::new_async().await;
+    for (symbol, body) in [
+        ("AAPL", global_quote("AAPL", "228.7500")),
+        ("MSFT", global_quote("MSFT", "512.1000")),
+        ("NOPE", fixture("alpha_vantage_empty_quote.json")),
+    ] {
+        server
+            .mock("GET", "/query")
+            .match_query(Matcher::UrlEncoded("symbol".into(), symbol.into()))
+            .with_body(body)
+            .create_async()
+            .await;
+    }
+
+    // One pool shared by every request in the batch
+    let http = reqwest::Client::new();
+    let source = AlphaVantage::with_client("test-key", ProviderConfig::new(server.url()), http);
+    let prices = source.fetch_spot_many(&["MSFT", "NOPE", "AAPL"]).await;
+
+    assert_eq!(prices.len(), 3);
+    assert_eq!(prices[0].as_ref().unwrap().price_usd, 512_100_000);
+    assert!(matches!(prices[1], Err(PriceOracleError::MissingSymbol(_))));
+    assert_eq!(prices[2].as_ref().unwrap().price_usd, 228_750_000);
+}
+
+#[tokio::test]
+async fn test_fetch_rate_limit() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/query")
+        .match_query(Matcher::Any)
+        .with_body(fixture("alpha_vantage_note.json"))
+        .create_async()
+        .await;
+
+    let source = AlphaVantage::with_config("test-key", ProviderConfig::new(server.url()));
+    assert!(matches!(source.fetch_spot("AAPL").await, Err(PriceOracleError::RateLimited(_))));
+}
+
+#[tokio::test]
+async fn test_fixture_source_is_async() {
+    let fixtures = FixturePriceSource::sample();
+    let prices = fixtures.fetch_spot_many(&["AAPL", "SOL"]).await;
+    assert!(prices.iter().all(|price| price.is_ok()));
+    assert_eq!(
+        fixtures.fetch_historical("SOL", "2025-09-01").await.unwrap().price_usd,
+        165_000_000
+    );
+}
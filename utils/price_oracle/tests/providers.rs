@@ -0,0 +1,272 @@
+//! Provider integration tests against a local mock HTTP server
+//!
+//! Each test serves a recorded response from tests/fixtures and points the
+//! provider at the server through ProviderConfig.
+
+use mockito::{Matcher, Server, ServerGuard};
+use price_oracle::{
+    AlphaVantage, AsyncPriceSource, CacheConfig, CachedPriceSource, CoinGecko, PriceOracleError, PriceSource,
+    ProviderConfig,
+};
+use std::time::Duration;
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("missing fixture {}", path))
+}
+
+fn coingecko(server: &ServerGuard) -> CoinGecko {
+    CoinGecko::with_config(ProviderConfig::new(server.url()))
+}
+
+fn alpha_vantage(server: &ServerGuard) -> AlphaVantage {
+    AlphaVantage::with_config("test-key", ProviderConfig::new(server.url()))
+}
+
+fn alpha_vantage_query(function: &str) -> Matcher {
+    Matcher::AllOf(vec![
+        Matcher::UrlEncoded("function".into(), function.into()),
+        Matcher::UrlEncoded("symbol".into(), "AAPL".into()),
+        Matcher::UrlEncoded("apikey".into(), "test-key".into()),
+    ])
+}
+
+#[test]
+fn test_coingecko_spot_sends_configured_user_agent() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/simple/price")
+        .match_query(Matcher::UrlEncoded("ids".into(), "solana".into()))
+        .match_header("user-agent", "keeper-ci/1.0")
+        .with_body(fixture("coingecko_simple_price.json"))
+        .create();
+
+    let config = ProviderConfig::new(server.url()).with_user_agent("keeper-ci/1.0");
+    let price = CoinGecko::with_config(config).spot("SOL").unwrap();
+
+    mock.assert();
+    assert_eq!(price.symbol, "SOL");
+    assert_eq!(price.price_usd, 150_250_000);
+}
+
+#[test]
+fn test_blocking_call_after_async_fetch_on_an_idle_runtime() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/simple/price")
+        .match_query(Matcher::UrlEncoded("ids".into(), "solana".into()))
+        .with_body(fixture("coingecko_simple_price.json"))
+        .expect(2)
+        .create();
+
+    // The async fetch runs on a current-thread runtime that sits idle during the blocking call
+    let config = ProviderConfig::new(server.url()).with_timeout(Duration::from_secs(2));
+    let source = CoinGecko::with_config(config);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    assert_eq!(runtime.block_on(source.fetch_spot("SOL")).unwrap().price_usd, 150_250_000);
+
+    assert_eq!(source.spot("SOL").unwrap().price_usd, 150_250_000);
+    mock.assert();
+}
+
+#[test]
+fn test_coingecko_historical() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/coins/solana/history")
+        .match_query(Matcher::UrlEncoded("date".into(), "01-09-2025".into()))
+        .with_body(fixture("coingecko_history.json"))
+        .create();
+
+    let price = coingecko(&server).historical("SOL", "2025-09-01").unwrap();
+
+    mock.assert();
+    assert_eq!(price.price_usd, 165_000_000);
+    assert_eq!(price.timestamp, 1_756_684_800); // 2025-09-01T00:00:00Z
+}
+
+#[test]
+fn test_coingecko_rate_limit() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/simple/price")
+        .match_query(Matcher::Any)
+        .with_status(429)
+        .with_body(fixture("coingecko_rate_limit.json"))
+        .create();
+
+    assert!(matches!(coingecko(&server).spot("SOL"), Err(PriceOracleError::RateLimited(_))));
+}
+
+#[test]
+fn test_alpha_vantage_spot() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/query")
+        .match_query(alpha_vantage_query("GLOBAL_QUOTE"))
+        .with_body(fixture("alpha_vantage_global_quote.json"))
+        .create();
+
+    let price = alpha_vantage(&server).spot("AAPL").unwrap();
+
+    mock.assert();
+    assert_eq!(price.price_usd, 228_750_000);
+}
+
+#[test]
+fn test_alpha_vantage_historical() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/query")
+        .match_query(alpha_vantage_query("TIME_SERIES_DAILY"))
+        .with_body(fixture("alpha_vantage_daily.json"))
+        .expect(2)
+        .create();
+
+    let source = alpha_vantage(&server);
+    assert_eq!(source.historical("AAPL", "2025-08-01").unwrap().price_usd, 225_500_000);
+    assert!(matches!(
+        source.historical("AAPL", "2025-08-02"),
+        Err(PriceOracleError::MissingDate { .. })
+    ));
+}
+
+#[test]
+fn test_cached_backfill_downloads_series_once() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/query")
+        .match_query(alpha_vantage_query("TIME_SERIES_DAILY"))
+        .with_body(fixture("alpha_vantage_daily.json"))
+        .expect(1)
+        .create();
+
+    let path = std::env::temp_dir().join(format!("price_oracle_providers_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let cache = CachedPriceSource::open(alpha_vantage(&server), CacheConfig::new(&path)).unwrap();
+    assert_eq!(cache.historical("AAPL", "2025-08-01").unwrap().price_usd, 225_500_000);
+    assert_eq!(cache.historical("AAPL", "2025-08-05").unwrap().price_usd, 228_750_000);
+    // A weekend inside the downloaded series is a miss without a second request
+    assert!(matches!(
+        cache.historical("AAPL", "2025-08-02"),
+        Err(PriceOracleError::MissingDate { .. })
+    ));
+    mock.assert();
+
+    // The cache file alone answers once the server is gone
+    drop(server);
+    let offline = CachedPriceSource::open(AlphaVantage::new("unused"), CacheConfig::new(&path).offline()).unwrap();
+    assert_eq!(offline.historical("AAPL", "2025-08-05").unwrap().price_usd, 228_750_000);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_alpha_vantage_rate_limit_payloads() {
+    // Alpha Vantage throttles with HTTP 200 and a "Note" or "Information" body
+    for name in ["alpha_vantage_note.json", "alpha_vantage_information.json"] {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/query")
+            .match_query(Matcher::Any)
+            .with_body(fixture(name))
+            .create();
+
+        assert!(
+            matches!(alpha_vantage(&server).spot("AAPL"), Err(PriceOracleError::RateLimited(_))),
+            "{} was not classified as a rate limit",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_errors_do_not_leak_api_key() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/query")
+        .match_query(alpha_vantage_query("GLOBAL_QUOTE"))
+        .with_status(429)
+        .create();
+    server
+        .mock("GET", "/query")
+        .match_query(alpha_vantage_query("TIME_SERIES_DAILY"))
+        .with_status(503)
+        .create();
+
+    let source = alpha_vantage(&server);
+    match source.spot("AAPL") {
+        Err(error @ PriceOracleError::RateLimited(_)) => {
+            let message = error.to_string();
+            assert!(message.contains("/query"), "{}", message);
+            assert!(!message.contains("test-key"), "{}", message);
+        }
+        other => panic!("expected rate limiting, got {:?}", other),
+    }
+    match source.daily_series("AAPL") {
+        Err(error @ PriceOracleError::Network(_)) => {
+            let message = error.to_string();
+            assert!(message.contains("503"), "{}", message);
+            assert!(!message.contains("test-key"), "{}", message);
+        }
+        other => panic!("expected a network error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_alpha_vantage_missing_symbol() {
+    for name in ["alpha_vantage_invalid_symbol.json", "alpha_vantage_empty_quote.json"] {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/query")
+            .match_query(Matcher::Any)
+            .with_body(fixture(name))
+            .create();
+
+        assert!(
+            matches!(alpha_vantage(&server).spot("AAPL"), Err(PriceOracleError::MissingSymbol(_))),
+            "{} was not classified as a missing symbol",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_server_error_and_malformed_body() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/simple/price")
+        .match_query(Matcher::Any)
+        .with_status(502)
+        .with_body("Bad Gateway")
+        .create();
+    server
+        .mock("GET", "/query")
+        .match_query(Matcher::Any)
+        .with_body("<html>maintenance</html>")
+        .create();
+
+    assert!(matches!(coingecko(&server).spot("SOL"), Err(PriceOracleError::Network(_))));
+    assert!(matches!(
+        alpha_vantage(&server).spot("AAPL"),
+        Err(PriceOracleError::MalformedResponse(_))
+    ));
+}
+
+#[test]
+fn test_timeout() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/simple/price")
+        .match_query(Matcher::Any)
+        .with_chunked_body(|writer| {
+            std::thread::sleep(Duration::from_millis(500));
+            writer.write_all(b"{}")
+        })
+        .create();
+
+    let config = ProviderConfig::new(server.url()).with_timeout(Duration::from_millis(100));
+    match CoinGecko::with_config(config).spot("SOL") {
+        Err(PriceOracleError::Network(error)) => assert!(error.is_timeout()),
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}